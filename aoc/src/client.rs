@@ -0,0 +1,133 @@
+use std::{
+    fs,
+    path::PathBuf,
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "aoc/",
+    env!("CARGO_PKG_VERSION"),
+    " (personal Advent of Code runner; set AOC_USER_AGENT to add contact details)"
+);
+
+/// Talks to the Advent of Code site on behalf of one user and year.
+pub struct Client {
+    base_url: String,
+    year: u32,
+    session: String,
+    user_agent: String,
+    throttle: Throttle,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(
+        base_url: &str,
+        year: u32,
+        session: &str,
+        user_agent: &str,
+        throttle: Throttle,
+    ) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            year,
+            session: session.trim().to_string(),
+            user_agent: user_agent.to_string(),
+            throttle,
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    pub fn year(&self) -> u32 {
+        self.year
+    }
+
+    fn url(&self, day: u32, suffix: &str) -> String {
+        format!("{}/{}/day/{day}{suffix}", self.base_url, self.year)
+    }
+
+    fn request(&self, method: &str, url: &str) -> Result<ureq::Request> {
+        if self.session.is_empty() {
+            bail!("A session token is needed, pass --session or set AOC_SESSION");
+        }
+        self.throttle.wait()?;
+        Ok(self
+            .agent
+            .request(method, url)
+            .set("User-Agent", &self.user_agent)
+            .set("Cookie", &format!("session={}", self.session)))
+    }
+
+    /// Downloads the puzzle input for `day`.
+    pub fn input(&self, day: u32) -> Result<String> {
+        let url = self.url(day, "/input");
+        let response = self.request("GET", &url)?.call();
+        read_response(response, &url)
+    }
+
+    /// Posts `answer` for one part of `day`, returning the page that comes back.
+    pub fn answer(&self, day: u32, part: u8, answer: &str) -> Result<String> {
+        let url = self.url(day, "/answer");
+        let level = part.to_string();
+        let response = self
+            .request("POST", &url)?
+            .send_form(&[("level", &level), ("answer", answer)]);
+        read_response(response, &url)
+    }
+}
+
+fn read_response(response: Result<ureq::Response, ureq::Error>, url: &str) -> Result<String> {
+    match response {
+        Ok(response) => Ok(response.into_string()?),
+        Err(ureq::Error::Status(400, _)) => {
+            bail!("{url} rejected the request (400), the session token has probably expired")
+        }
+        Err(ureq::Error::Status(404, _)) => {
+            bail!("{url} was not found (404), the puzzle might not be unlocked yet")
+        }
+        Err(ureq::Error::Status(code, response)) => bail!(
+            "{url} failed with {code}: {}",
+            response.into_string().unwrap_or_default().trim()
+        ),
+        Err(e) => Err(e).with_context(|| format!("Failed to reach {url}")),
+    }
+}
+
+/// Spaces requests out by at least `interval`, even across separate runs,
+/// by remembering when the last one was made in a file.
+pub struct Throttle {
+    path: PathBuf,
+    interval: Duration,
+}
+
+impl Throttle {
+    pub fn new(path: PathBuf, interval: Duration) -> Self {
+        Self { path, interval }
+    }
+
+    fn wait(&self) -> Result<()> {
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        if let Ok(last) = fs::read_to_string(&self.path) {
+            let last = Duration::from_millis(
+                last.trim()
+                    .parse()
+                    .map_err(|_| anyhow!("Corrupt throttle file {}", self.path.display()))?,
+            );
+            if let Some(remaining) = (last + self.interval).checked_sub(now) {
+                thread::sleep(remaining);
+            }
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?;
+        fs::write(&self.path, now.as_millis().to_string())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,101 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Result;
+
+use crate::client::Client;
+
+/// Puzzle inputs kept on disk so each one is only ever downloaded once.
+pub struct InputCache {
+    dir: PathBuf,
+}
+
+impl InputCache {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    fn path(&self, year: u32, day: u32) -> PathBuf {
+        self.dir
+            .join(year.to_string())
+            .join(format!("day{day:02}.txt"))
+    }
+
+    /// Returns the input for `day`, downloading it with `client` only if it
+    /// isn't already cached.
+    pub fn get(&self, client: &Client, day: u32) -> Result<String> {
+        let path = self.path(client.year(), day);
+        if let Ok(input) = fs::read_to_string(&path) {
+            return Ok(input);
+        }
+        let input = client.input(day)?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, &input)?;
+        Ok(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+    use crate::client::Throttle;
+    use crate::stub::Stub;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn client(stub: &Stub, dir: &std::path::Path, interval: Duration) -> Client {
+        let throttle = Throttle::new(dir.join("last-request"), interval);
+        Client::new(&stub.url, 2024, "abc123", "aoc-tests", throttle)
+    }
+
+    #[test]
+    fn downloads_once_then_serves_from_cache() {
+        let dir = scratch("cache");
+        let stub = Stub::serve(vec![(200, "1   2\n3   4\n")]);
+        let client = client(&stub, &dir, Duration::ZERO);
+        let cache = InputCache::new(dir.clone());
+
+        assert_eq!(cache.get(&client, 1).unwrap(), "1   2\n3   4\n");
+        assert_eq!(cache.get(&client, 1).unwrap(), "1   2\n3   4\n");
+        let requests = stub.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "GET");
+        assert_eq!(requests[0].path, "/2024/day/1/input");
+        assert_eq!(requests[0].header("Cookie"), Some("session=abc123"));
+        assert_eq!(requests[0].header("User-Agent"), Some("aoc-tests"));
+        assert!(dir.join("2024/day01.txt").exists());
+    }
+
+    #[test]
+    fn errors_are_not_cached() {
+        let dir = scratch("errors");
+        let stub = Stub::serve(vec![(404, "Not Found"), (200, "input")]);
+        let client = client(&stub, &dir, Duration::ZERO);
+        let cache = InputCache::new(dir);
+
+        let error = cache.get(&client, 25).unwrap_err().to_string();
+        assert!(error.contains("not be unlocked yet"), "{error}");
+        assert_eq!(cache.get(&client, 25).unwrap(), "input");
+        assert_eq!(stub.requests().len(), 2);
+    }
+
+    #[test]
+    fn requests_are_throttled() {
+        let dir = scratch("throttle");
+        let stub = Stub::serve(vec![(200, "one"), (200, "two")]);
+        let client = client(&stub, &dir, Duration::from_millis(300));
+        let cache = InputCache::new(dir);
+
+        let start = Instant::now();
+        cache.get(&client, 1).unwrap();
+        cache.get(&client, 2).unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(300));
+    }
+}
@@ -0,0 +1,203 @@
+use anyhow::{anyhow, bail, Result};
+use client::{Client, Throttle, DEFAULT_BASE_URL, DEFAULT_USER_AGENT};
+use input::InputCache;
+use run::{run_days, table, DAYS};
+use std::{
+    env,
+    fs::write,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+use submit::{submit, History};
+mod client;
+mod input;
+mod run;
+#[cfg(test)]
+mod stub;
+mod submit;
+
+fn main() -> Result<()> {
+    let args = Args::parse(env::args().skip(1))?;
+    match args.command {
+        Command::Fetch { day, ref out } => {
+            let input = InputCache::new(args.cache.clone()).get(&args.client(), day)?;
+            let out = out
+                .clone()
+                .unwrap_or_else(|| PathBuf::from(format!("day{day:02}/input.txt")));
+            if out
+                .parent()
+                .is_some_and(|dir| !dir.as_os_str().is_empty() && !dir.exists())
+            {
+                print!("{input}");
+            } else {
+                write(&out, input)?;
+                println!("Wrote day {day} input to {}", out.display());
+            }
+        }
+        Command::Submit {
+            day,
+            part,
+            ref answer,
+        } => {
+            let path = args
+                .cache
+                .join(args.year.to_string())
+                .join("history")
+                .join(format!("day{day:02}.txt"));
+            let mut history = History::load(path)?;
+            let verdict = submit(&args.client(), &mut history, day, part, answer)?;
+            println!("Day {day} part {part}: {answer} - {verdict}");
+        }
+        Command::Run { ref days, parallel } => {
+            let start = Instant::now();
+            let reports = run_days(&args.root, days, parallel, args.timeout);
+            print!("{}", table(&reports, start.elapsed()));
+            if reports.iter().any(|report| report.errored()) {
+                bail!("Some days failed");
+            }
+        }
+    }
+    Ok(())
+}
+
+enum Command {
+    Fetch { day: u32, out: Option<PathBuf> },
+    Submit { day: u32, part: u8, answer: String },
+    Run { days: Vec<String>, parallel: bool },
+}
+
+/// Usage: aoc [OPTIONS] fetch DAY [--out PATH]
+///        aoc [OPTIONS] submit DAY PART ANSWER
+///        aoc [OPTIONS] run (--all | DAY | dayNN) [--parallel]
+///
+/// fetch downloads a day's input once into the cache and copies it to PATH,
+/// by default dayNN/input.txt, or prints it if that directory is missing.
+/// submit posts an answer, unless the answers already given for that part
+/// show it can't be right, and remembers what the site said about it.
+/// run builds and runs one day, or every day with --all, and tabulates the
+/// answers and timings each printed. --parallel runs the days at once,
+/// which is quicker overall but makes the individual timings less reliable.
+///
+/// Options, most of which can also be set in the environment:
+///   --session TOKEN     session cookie from the site (AOC_SESSION)
+///   --year YEAR         puzzle year, 2024 by default
+///   --cache DIR         where inputs and answers are kept, .aoc by default
+///                       (AOC_CACHE)
+///   --user-agent AGENT  sent with every request (AOC_USER_AGENT)
+///   --base-url URL      the site to talk to, for testing against a stub
+///   --interval SECONDS  the least time between requests, 5 by default
+///   --root DIR          where the dayNN crates live, . by default
+///   --timeout SECONDS   how long run lets each day take before killing it
+///                       and counting it as failed, 60 by default
+struct Args {
+    command: Command,
+    session: String,
+    year: u32,
+    cache: PathBuf,
+    user_agent: String,
+    base_url: String,
+    interval: Duration,
+    root: PathBuf,
+    timeout: Duration,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut session = env::var("AOC_SESSION").unwrap_or_default();
+        let mut year = 2024;
+        let mut cache = PathBuf::from(env::var("AOC_CACHE").unwrap_or(".aoc".to_string()));
+        let mut user_agent = env::var("AOC_USER_AGENT").unwrap_or(DEFAULT_USER_AGENT.to_string());
+        let mut base_url = DEFAULT_BASE_URL.to_string();
+        let mut interval = Duration::from_secs(5);
+        let mut root = PathBuf::from(".");
+        let mut timeout = Duration::from_secs(60);
+        let mut out = None;
+        let mut all = false;
+        let mut parallel = false;
+        let mut positional = vec![];
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--all" => all = true,
+                "--parallel" => parallel = true,
+                _ if !arg.starts_with("--") => positional.push(arg),
+                _ => {
+                    let value = args
+                        .next()
+                        .ok_or(anyhow!("Missing value for argument {arg}"))?;
+                    match arg.as_str() {
+                        "--session" => session = value,
+                        "--year" => year = value.parse()?,
+                        "--cache" => cache = PathBuf::from(value),
+                        "--user-agent" => user_agent = value,
+                        "--base-url" => base_url = value,
+                        "--interval" => interval = Duration::from_secs_f64(value.parse()?),
+                        "--out" => out = Some(PathBuf::from(value)),
+                        "--root" => root = PathBuf::from(value),
+                        "--timeout" => timeout = Duration::from_secs_f64(value.parse()?),
+                        _ => bail!("Unknown argument {arg}"),
+                    }
+                }
+            }
+        }
+
+        let command = match positional.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+            ["fetch", day] => Command::Fetch {
+                day: parse_day(day)?,
+                out,
+            },
+            ["submit", day, part, answer] => Command::Submit {
+                day: parse_day(day)?,
+                part: match part {
+                    "1" => 1,
+                    "2" => 2,
+                    _ => bail!("Part must be 1 or 2, got {part}"),
+                },
+                answer: answer.to_string(),
+            },
+            ["run"] if all => Command::Run {
+                days: DAYS.iter().map(|day| day.to_string()).collect(),
+                parallel,
+            },
+            ["run", day] if !all => Command::Run {
+                days: vec![match day.starts_with("day") {
+                    true => day.to_string(),
+                    false => format!("day{:02}", parse_day(day)?),
+                }],
+                parallel,
+            },
+            _ => bail!(
+                "Expected a command, e.g. `aoc fetch 6`, `aoc submit 6 1 41` or `aoc run --all`"
+            ),
+        };
+        Ok(Args {
+            command,
+            session,
+            year,
+            cache,
+            user_agent,
+            base_url,
+            interval,
+            root,
+            timeout,
+        })
+    }
+
+    fn client(&self) -> Client {
+        let throttle = Throttle::new(self.cache.join("last-request"), self.interval);
+        Client::new(
+            &self.base_url,
+            self.year,
+            &self.session,
+            &self.user_agent,
+            throttle,
+        )
+    }
+}
+
+fn parse_day(day: &str) -> Result<u32> {
+    let day = day.parse()?;
+    if !(1..=25).contains(&day) {
+        bail!("Day must be between 1 and 25, got {day}");
+    }
+    Ok(day)
+}
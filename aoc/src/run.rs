@@ -0,0 +1,397 @@
+use std::{
+    io::{self, IsTerminal, Read},
+    path::{Path, PathBuf},
+    process::{Child, Command, Output, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use humantime::format_duration;
+use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+/// The directories of every day that `run --all` runs.
+pub const DAYS: &[&str] = &[
+    "day01", "day02", "day03", "day04", "day05", "day06", "day07", "day08", "day09", "day10",
+    "day11", "day12", "day13", "day14", "day15", "dayAA",
+];
+
+/// What one part of a day printed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartReport {
+    pub answer: Result<String, String>,
+    pub took: Option<String>,
+}
+
+/// How a day's run went, read back out of what it printed.
+#[derive(Clone, Debug)]
+pub struct DayReport {
+    pub day: String,
+    pub parse_took: Option<String>,
+    pub parts: [Option<PartReport>; 2],
+    /// Set when the day couldn't be run at all or exited with an error.
+    pub failure: Option<String>,
+    pub wall: Duration,
+}
+
+impl DayReport {
+    pub fn errored(&self) -> bool {
+        self.failure.is_some()
+            || self
+                .parts
+                .iter()
+                .any(|part| part.as_ref().is_none_or(|part| part.answer.is_err()))
+    }
+}
+
+/// Builds and runs every day in `days`, one after another or all at once.
+/// Each is built before the clock starts and its binary run directly, so
+/// neither compiling nor cargo count towards the wall time, but when run in
+/// parallel the days still compete with each other for the CPU. A day still
+/// running after `timeout` is killed and counted as failed.
+pub fn run_days(root: &Path, days: &[String], parallel: bool, timeout: Duration) -> Vec<DayReport> {
+    if parallel {
+        days.par_iter()
+            .map(|day| run_day(root, day, timeout))
+            .collect()
+    } else {
+        days.iter().map(|day| run_day(root, day, timeout)).collect()
+    }
+}
+
+fn run_day(root: &Path, day: &str, timeout: Duration) -> DayReport {
+    let dir = root.join(day);
+    let failed = |failure: String| DayReport {
+        day: day.to_string(),
+        parse_took: None,
+        parts: [None, None],
+        failure: Some(failure),
+        wall: Duration::ZERO,
+    };
+
+    let executable = match build(&dir) {
+        Ok(executable) => executable,
+        Err(failure) => return failed(failure),
+    };
+    let start = Instant::now();
+    let output = output_within(
+        Command::new(&executable)
+            .current_dir(&dir)
+            .env("RUST_BACKTRACE", "0")
+            .env("RUST_LIB_BACKTRACE", "0"),
+        timeout,
+    );
+    let wall = start.elapsed();
+    match output {
+        Ok((output, timed_out)) => {
+            let mut report = parse_output(day, &output);
+            report.wall = wall;
+            if timed_out {
+                report.failure = Some(format!("Timed out after {}", format_duration(timeout)));
+            }
+            report
+        }
+        Err(e) => failed(format!("Failed to run {}: {e}", executable.display())),
+    }
+}
+
+/// Like `Command::output`, but kills the program if it's still running after
+/// `timeout`, returning whatever it printed before then and whether it had
+/// to be killed. Only the program itself is killed, so anything it started
+/// and left holding its output open is waited for.
+fn output_within(command: &mut Command, timeout: Duration) -> io::Result<(Output, bool)> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    // Read both pipes as the program runs, so it never blocks on a full one.
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+    let deadline = Instant::now() + timeout;
+    let (status, timed_out) = loop {
+        if let Some(status) = child.try_wait()? {
+            break (status, false);
+        }
+        if Instant::now() >= deadline {
+            break (kill(&mut child)?, true);
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    let output = Output {
+        status,
+        stdout: stdout.join().unwrap_or_default(),
+        stderr: stderr.join().unwrap_or_default(),
+    };
+    Ok((output, timed_out))
+}
+
+fn read_in_background(pipe: Option<impl Read + Send + 'static>) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut bytes = vec![];
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut bytes);
+        }
+        bytes
+    })
+}
+
+fn kill(child: &mut Child) -> io::Result<std::process::ExitStatus> {
+    // It may have finished between checking and killing it, which is fine.
+    let _ = child.kill();
+    child.wait()
+}
+
+/// Builds the day in release mode and finds its binary in cargo's messages,
+/// since the package names don't always match the directories.
+fn build(dir: &Path) -> Result<PathBuf, String> {
+    let output = Command::new("cargo")
+        .args(["build", "--release", "--message-format=json"])
+        .current_dir(dir)
+        .output()
+        .map_err(|e| format!("Failed to run cargo: {e}"))?;
+    if !output.status.success() {
+        return Err(last_line(&output.stderr, "Build failed"));
+    }
+    let key = "\"executable\":\"";
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let start = line.find(key)? + key.len();
+            let end = start + line[start..].find('"')?;
+            Some(PathBuf::from(&line[start..end]))
+        })
+        .next_back()
+        .ok_or(format!("No binary was built in {}", dir.display()))
+}
+
+/// The last thing a failing program says is usually why it failed.
+fn last_line(bytes: &[u8], fallback: &str) -> String {
+    let text = String::from_utf8_lossy(bytes);
+    text.lines()
+        .map(str::trim)
+        .rfind(|line| !line.is_empty())
+        .unwrap_or(fallback)
+        .to_string()
+}
+
+/// Reads the template's "Part One: Ok(..) (Took ..)" style lines. Days word
+/// their timings slightly differently, so timings are kept as printed.
+pub fn parse_output(day: &str, output: &Output) -> DayReport {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut report = DayReport {
+        day: day.to_string(),
+        parse_took: None,
+        parts: [None, None],
+        failure: None,
+        wall: Duration::ZERO,
+    };
+    for line in stdout.lines() {
+        if line.starts_with("Processing Complete") || line.starts_with("Processed input") {
+            report.parse_took = took(line);
+        } else if let Some(rest) = line.strip_prefix("Part One: ") {
+            report.parts[0] = Some(part(rest));
+        } else if let Some(rest) = line.strip_prefix("Part Two: ") {
+            report.parts[1] = Some(part(rest));
+        }
+    }
+    if !output.status.success() {
+        report.failure = Some(last_line(&output.stderr, "Exited with an error"));
+    }
+    report
+}
+
+fn took(line: &str) -> Option<String> {
+    let (_, took) = line.rsplit_once("(Took")?;
+    Some(
+        took.trim_start_matches(':')
+            .trim_end_matches(')')
+            .trim()
+            .trim_matches('"')
+            .to_string(),
+    )
+}
+
+fn part(rest: &str) -> PartReport {
+    let answer = match rest.rsplit_once(" (Took") {
+        Some((answer, _)) => answer,
+        None => rest,
+    };
+    let answer = if let Some(ok) = answer.strip_prefix("Ok(") {
+        Ok(ok.strip_suffix(')').unwrap_or(ok).to_string())
+    } else if let Some(err) = answer.strip_prefix("Err(") {
+        Err(err.strip_suffix(')').unwrap_or(err).to_string())
+    } else {
+        Ok(answer.to_string())
+    };
+    PartReport {
+        answer,
+        took: took(rest),
+    }
+}
+
+/// Lays the reports out as a table with the total runtime underneath,
+/// marking days that errored, in red when printing to a terminal.
+pub fn table(reports: &[DayReport], total: Duration) -> String {
+    let colour = std::io::stdout().is_terminal();
+    let headers = [
+        "Day", "Parse", "Part One", "Took", "Part Two", "Took", "Wall", "Status",
+    ];
+    let mut rows: Vec<[String; 8]> = vec![headers.map(String::from)];
+    for report in reports {
+        let [one, two] = report.parts.clone().map(|part| match part {
+            None => ("-".to_string(), String::new()),
+            Some(part) => (
+                match part.answer {
+                    Ok(answer) => answer,
+                    Err(error) => format!("error: {error}"),
+                },
+                part.took.unwrap_or_default(),
+            ),
+        });
+        let status = match (&report.failure, report.errored()) {
+            (Some(failure), _) => format!("FAILED: {failure}"),
+            (None, true) => "FAILED".to_string(),
+            (None, false) => "ok".to_string(),
+        };
+        rows.push([
+            report.day.clone(),
+            report.parse_took.clone().unwrap_or_default(),
+            one.0,
+            one.1,
+            two.0,
+            two.1,
+            format_duration(round(report.wall)).to_string(),
+            status,
+        ]);
+    }
+
+    let mut widths = [0; 8];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let mut string = String::new();
+    for (row, report) in rows
+        .iter()
+        .zip([None].into_iter().chain(reports.iter().map(Some)))
+    {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect();
+        let line = cells.join(" | ");
+        if colour && report.is_some_and(DayReport::errored) {
+            string.push_str(&format!("\x1b[31m{}\x1b[0m\n", line.trim_end()));
+        } else {
+            string.push_str(line.trim_end());
+            string.push('\n');
+        }
+    }
+    let failed = reports.iter().filter(|report| report.errored()).count();
+    string.push_str(&format!(
+        "{} days, {failed} failed, total runtime {}\n",
+        reports.len(),
+        format_duration(round(total))
+    ));
+    string
+}
+
+/// Drops the sub-millisecond noise so durations stay readable.
+fn round(duration: Duration) -> Duration {
+    Duration::from_millis(duration.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::ExitStatus;
+
+    use super::*;
+
+    fn output(stdout: &str, code: i32) -> Output {
+        Output {
+            status: ExitStatus::from_raw(code << 8),
+            stdout: stdout.as_bytes().to_vec(),
+            stderr: b"Error: No such file or directory (os error 2)\n".to_vec(),
+        }
+    }
+
+    #[test]
+    fn reads_both_output_styles() {
+        let template = "Lines in Input: 6\nProcessing Complete. (Took \"66us 113ns\")\nPart One: Ok(11) (Took \"5us\")\nPart Two: Ok(31) (Took \"7us\")\n";
+        let report = parse_output("day01", &output(template, 0));
+        assert!(!report.errored());
+        assert_eq!(report.parse_took.as_deref(), Some("66us 113ns"));
+        assert_eq!(
+            report.parts[0],
+            Some(PartReport {
+                answer: Ok("11".to_string()),
+                took: Some("5us".to_string())
+            })
+        );
+
+        let older = "Processed input [8 lines] (Took: 1ms 54us)\nPart One: Ok(36) (Took: 207us)\nPart Two: Ok(81) (Took: 83us)\n";
+        let report = parse_output("day10", &output(older, 0));
+        assert!(!report.errored());
+        assert_eq!(report.parse_took.as_deref(), Some("1ms 54us"));
+        assert_eq!(
+            report.parts[1].as_ref().unwrap().took.as_deref(),
+            Some("83us")
+        );
+    }
+
+    #[test]
+    fn flags_errors() {
+        let stub =
+            "Part One: Ok(22) (Took \"33us\")\nPart Two: Err(Unimplemented) (Took \"6us\")\n";
+        let report = parse_output("dayAA", &output(stub, 0));
+        assert!(report.errored());
+        assert_eq!(
+            report.parts[1].as_ref().unwrap().answer,
+            Err("Unimplemented".to_string())
+        );
+
+        let missing_input = parse_output("day06", &output("", 1));
+        assert!(missing_input.errored());
+        assert_eq!(
+            missing_input.failure.as_deref(),
+            Some("Error: No such file or directory (os error 2)")
+        );
+
+        let table = table(&[report, missing_input], Duration::from_millis(1500));
+        assert!(table.contains("error: Unimplemented"));
+        assert!(table.contains("FAILED: Error: No such file"));
+        assert!(table.ends_with("2 days, 2 failed, total runtime 1s 500ms\n"));
+    }
+
+    #[test]
+    fn kills_programs_that_run_too_long() {
+        let start = Instant::now();
+        let (output, timed_out) = output_within(
+            Command::new("sh").args(["-c", "echo 'Part One: Ok(1)'; exec sleep 10"]),
+            Duration::from_millis(200),
+        )
+        .unwrap();
+        assert!(timed_out);
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert!(!output.status.success());
+        assert_eq!(
+            parse_output("day01", &output).parts[0]
+                .as_ref()
+                .unwrap()
+                .answer,
+            Ok("1".to_string())
+        );
+
+        let (output, timed_out) = output_within(
+            Command::new("sh").args(["-c", "echo done"]),
+            Duration::from_secs(10),
+        )
+        .unwrap();
+        assert!(!timed_out);
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"done\n");
+    }
+}
@@ -0,0 +1,296 @@
+use std::{
+    fmt::Display,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::client::Client;
+
+/// What the site made of a submitted answer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint as to which way.
+    Wrong,
+    /// Answered too recently, try again after this many seconds.
+    Wait(u64),
+    /// The part has already been solved, or isn't unlocked yet.
+    WrongLevel,
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => f.write_str("That's the right answer!"),
+            Verdict::TooHigh => f.write_str("Wrong, too high"),
+            Verdict::TooLow => f.write_str("Wrong, too low"),
+            Verdict::Wrong => f.write_str("Wrong"),
+            Verdict::Wait(seconds) => write!(f, "Answered too recently, wait {seconds}s"),
+            Verdict::WrongLevel => f.write_str("Not the right level, is it already solved?"),
+        }
+    }
+}
+
+impl Verdict {
+    /// Reads the verdict out of the page that comes back from an answer.
+    pub fn parse(page: &str) -> Result<Verdict> {
+        if page.contains("That's the right answer") {
+            Ok(Verdict::Correct)
+        } else if page.contains("your answer is too high") {
+            Ok(Verdict::TooHigh)
+        } else if page.contains("your answer is too low") {
+            Ok(Verdict::TooLow)
+        } else if page.contains("That's not the right answer") {
+            Ok(Verdict::Wrong)
+        } else if page.contains("You gave an answer too recently") {
+            Ok(Verdict::Wait(parse_wait(page).unwrap_or(60)))
+        } else if page.contains("You don't seem to be solving the right level") {
+            Ok(Verdict::WrongLevel)
+        } else {
+            bail!("Couldn't understand the response to the answer:\n{page}")
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Verdict::Correct => "correct",
+            Verdict::TooHigh => "too-high",
+            Verdict::TooLow => "too-low",
+            Verdict::Wrong => "wrong",
+            Verdict::Wait(_) => "wait",
+            Verdict::WrongLevel => "wrong-level",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Verdict> {
+        match name {
+            "correct" => Some(Verdict::Correct),
+            "too-high" => Some(Verdict::TooHigh),
+            "too-low" => Some(Verdict::TooLow),
+            "wrong" => Some(Verdict::Wrong),
+            _ => None,
+        }
+    }
+}
+
+/// Reads "You have 1m 23s left to wait" as 83 seconds.
+fn parse_wait(page: &str) -> Option<u64> {
+    let start = page.find("You have ")? + "You have ".len();
+    let end = start + page[start..].find(" left to wait")?;
+    let mut seconds = 0;
+    for part in page[start..end].split_whitespace() {
+        let (number, unit) = part.split_at(part.find(|c: char| !c.is_ascii_digit())?);
+        let number: u64 = number.parse().ok()?;
+        seconds += match unit {
+            "h" => number * 3600,
+            "m" => number * 60,
+            "s" => number,
+            _ => return None,
+        };
+    }
+    Some(seconds)
+}
+
+/// Every answer given for one day, kept in a file with one tab separated
+/// `part answer verdict` line per answer.
+pub struct History {
+    path: PathBuf,
+    entries: Vec<(u8, String, Verdict)>,
+}
+
+impl History {
+    pub fn load(path: PathBuf) -> Result<Self> {
+        let mut entries = vec![];
+        if let Ok(contents) = fs::read_to_string(&path) {
+            for line in contents.lines().filter(|l| !l.is_empty()) {
+                let mut fields = line.split('\t');
+                let (Some(part), Some(answer), Some(verdict)) =
+                    (fields.next(), fields.next(), fields.next())
+                else {
+                    bail!("Malformed line in {}: {line}", path.display());
+                };
+                let verdict = Verdict::from_name(verdict)
+                    .ok_or(anyhow!("Unknown verdict in {}: {line}", path.display()))?;
+                entries.push((part.parse()?, answer.to_string(), verdict));
+            }
+        }
+        Ok(Self { path, entries })
+    }
+
+    /// Refuses answers that can't be right given what the site has already
+    /// said: the part is solved, the same answer was wrong before, or a
+    /// numeric answer falls outside the too-low and too-high answers.
+    pub fn check(&self, part: u8, answer: &str) -> Result<()> {
+        let mut lowest: Option<i128> = None;
+        let mut highest: Option<i128> = None;
+        let number: Option<i128> = answer.parse().ok();
+        for (_, previous, verdict) in self.entries.iter().filter(|(p, _, _)| *p == part) {
+            if *verdict == Verdict::Correct {
+                bail!("Part {part} is already solved, the answer was {previous}");
+            }
+            if previous == answer {
+                bail!("{answer} was already submitted for part {part}: {verdict}");
+            }
+            let Ok(previous) = previous.parse::<i128>() else {
+                continue;
+            };
+            match verdict {
+                Verdict::TooLow => lowest = lowest.max(Some(previous)),
+                Verdict::TooHigh => highest = Some(highest.map_or(previous, |h| h.min(previous))),
+                _ => {}
+            }
+        }
+        if let (Some(number), Some(lowest)) = (number, lowest) {
+            if number <= lowest {
+                bail!("{answer} is too low, {lowest} was already too low for part {part}");
+            }
+        }
+        if let (Some(number), Some(highest)) = (number, highest) {
+            if number >= highest {
+                bail!("{answer} is too high, {highest} was already too high for part {part}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Remembers a verdict. Waits and level mix-ups say nothing about the
+    /// answer itself, so they aren't kept.
+    pub fn record(&mut self, part: u8, answer: &str, verdict: Verdict) -> Result<()> {
+        if matches!(verdict, Verdict::Wait(_) | Verdict::WrongLevel) {
+            return Ok(());
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{part}\t{answer}\t{}", verdict.name())?;
+        self.entries.push((part, answer.to_string(), verdict));
+        Ok(())
+    }
+}
+
+/// Checks `answer` against the history, submits it and records the verdict.
+pub fn submit(
+    client: &Client,
+    history: &mut History,
+    day: u32,
+    part: u8,
+    answer: &str,
+) -> Result<Verdict> {
+    let answer = answer.trim();
+    if answer.is_empty() || answer.contains(char::is_whitespace) {
+        bail!("Answer must be a single non-empty word, got {answer:?}");
+    }
+    history.check(part, answer)?;
+    let verdict = Verdict::parse(&client.answer(day, part, answer)?)?;
+    history.record(part, answer, verdict)?;
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::client::Throttle;
+    use crate::stub::Stub;
+
+    fn page(message: &str) -> String {
+        format!("<html><body><main><article><p>{message}</p></article></main></body></html>")
+    }
+
+    #[test]
+    fn parses_verdicts() {
+        let cases = [
+            ("That's the right answer! You are one gold star closer.", Verdict::Correct),
+            ("That's not the right answer; your answer is too high.", Verdict::TooHigh),
+            ("That's not the right answer; your answer is too low.", Verdict::TooLow),
+            ("That's not the right answer.  If you're stuck, ...", Verdict::Wrong),
+            (
+                "You gave an answer too recently; you have to wait after submitting an answer before trying again.  You have 1m 23s left to wait.",
+                Verdict::Wait(83),
+            ),
+            ("You gave an answer too recently. You have 9s left to wait.", Verdict::Wait(9)),
+            ("You don't seem to be solving the right level.  Did you already complete it?", Verdict::WrongLevel),
+        ];
+        for (message, verdict) in cases {
+            assert_eq!(
+                Verdict::parse(&page(message)).unwrap(),
+                verdict,
+                "{message}"
+            );
+        }
+        assert!(Verdict::parse(&page("Something else entirely")).is_err());
+    }
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("aoc-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn history_learns_bounds() {
+        let path = scratch("history").join("day01.txt");
+        let mut history = History::load(path.clone()).unwrap();
+        history.record(1, "100", Verdict::TooLow).unwrap();
+        history.record(1, "500", Verdict::TooHigh).unwrap();
+        history.record(1, "300", Verdict::Wrong).unwrap();
+        history.record(1, "200", Verdict::Wait(30)).unwrap();
+
+        let history = History::load(path).unwrap();
+        assert!(history.check(1, "100").is_err());
+        assert!(history.check(1, "50").is_err());
+        assert!(history.check(1, "500").is_err());
+        assert!(history.check(1, "9000").is_err());
+        assert!(history.check(1, "300").is_err());
+        assert!(history.check(1, "200").is_ok());
+        assert!(history.check(1, "abc").is_ok());
+        // Part two has its own bounds.
+        assert!(history.check(2, "50").is_ok());
+    }
+
+    #[test]
+    fn submits_and_records() {
+        let dir = scratch("submit");
+        let stub = Stub::serve(vec![
+            (
+                200,
+                &page("That's not the right answer; your answer is too low."),
+            ),
+            (200, &page("That's the right answer!")),
+        ]);
+        let throttle = Throttle::new(dir.join("last-request"), Duration::ZERO);
+        let client = Client::new(&stub.url, 2024, "abc123", "aoc-tests", throttle);
+        let mut history = History::load(dir.join("day07.txt")).unwrap();
+
+        assert_eq!(
+            submit(&client, &mut history, 7, 1, "41").unwrap(),
+            Verdict::TooLow
+        );
+        assert!(submit(&client, &mut history, 7, 1, "40").is_err());
+        assert_eq!(
+            submit(&client, &mut history, 7, 1, "42").unwrap(),
+            Verdict::Correct
+        );
+        assert!(submit(&client, &mut history, 7, 1, "43").is_err());
+
+        let requests = stub.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/2024/day/7/answer");
+        assert_eq!(requests[1].body, "level=1&answer=42");
+        assert_eq!(
+            fs::read_to_string(dir.join("day07.txt")).unwrap(),
+            "1\t41\ttoo-low\n1\t42\tcorrect\n"
+        );
+    }
+}
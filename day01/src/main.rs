@@ -1,53 +1,168 @@
-use std::{collections::HashMap, error::Error, fs::read_to_string};
+use anyhow::{anyhow, bail, Context, Result};
+use humantime::format_duration;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{env, fs::read_to_string, time::Instant};
+mod stats;
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let input = read_to_string("input.txt")?;
-    println!("Lines in Input: {}", input.len());
-    let output = process_input(input)?;
-    println!("{:?}", output);
+type ProcessedInput = (Vec<i32>, Vec<i32>);
+type Output = i64;
+
+fn main() -> Result<()> {
+    let args = Args::parse(env::args().skip(1))?;
+    let input = match args.generate {
+        Some(count) => generate_input(count, args.seed),
+        None => read_to_string("input.txt")?,
+    };
+    println!("Lines in Input: {}", input.lines().count());
+
+    let processing_start = Instant::now();
+    let processed = process_input(input)?;
+    let time_processing = processing_start.elapsed();
+    println!(
+        "Processing Complete. (Took {:?})",
+        format_duration(time_processing).to_string()
+    );
+
+    let part_one_start = Instant::now();
+    let part_one = solve_part_one(processed.clone());
+    let time_one = part_one_start.elapsed();
+    println!(
+        "Part One: {:?} (Took {:?})",
+        part_one,
+        format_duration(time_one).to_string()
+    );
+
+    let part_two_start = Instant::now();
+    let part_two = solve_part_two(processed.clone());
+    let time_two = part_two_start.elapsed();
+    println!(
+        "Part Two: {:?} (Took {:?})",
+        part_two,
+        format_duration(time_two).to_string()
+    );
+
+    if args.stats {
+        print_stats(&processed);
+    }
     Ok(())
 }
 
-fn process_input(input: String) -> Result<String, Box<dyn Error>> {
+/// Usage: day01 [--stats] [--generate COUNT [--seed SEED]]
+/// --generate swaps input.txt for COUNT random pairs of locations.
+struct Args {
+    stats: bool,
+    generate: Option<usize>,
+    seed: u64,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Args {
+            stats: false,
+            generate: None,
+            seed: 0,
+        };
+        while let Some(flag) = args.next() {
+            if flag == "--stats" {
+                parsed.stats = true;
+                continue;
+            }
+            let value = args
+                .next()
+                .ok_or(anyhow!("Missing value for argument {flag}"))?;
+            match flag.as_str() {
+                "--generate" => parsed.generate = Some(value.parse()?),
+                "--seed" => parsed.seed = value.parse()?,
+                _ => bail!("Unknown argument {flag}"),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+fn generate_input(count: usize, seed: u64) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut input = String::new();
+    for _ in 0..count {
+        let left: i32 = rng.gen_range(10000..100000);
+        let right: i32 = rng.gen_range(10000..100000);
+        input.push_str(&format!("{left}   {right}\n"));
+    }
+    input
+}
+
+fn process_input(input: String) -> Result<ProcessedInput> {
     let lines = input.lines().filter(|l| !l.is_empty());
     let mut left = vec![];
     let mut right = vec![];
     for line in lines {
         let mut line_vec = line.split_whitespace();
-        let first = line_vec.next().ok_or("Invalid Input Line".to_owned())?;
-        let last = line_vec.next().ok_or("Invalid Input line".to_owned())?;
-        left.push(first.parse()?);
-        right.push(last.parse()?);
+        let first = line_vec.next().ok_or(anyhow!("Invalid Input Line"))?;
+        let last = line_vec.next().ok_or(anyhow!("Invalid Input line"))?;
+        left.push(
+            first
+                .parse()
+                .with_context(|| format!("Failed to parse {first}"))?,
+        );
+        right.push(
+            last.parse()
+                .with_context(|| format!("Failed to parse {last}"))?,
+        );
     }
-    solve_part_two(left, right)
-    // solve_part_one(left, right)
+    Ok((left, right))
 }
 
-fn solve_part_two(left: Vec<i32>, right: Vec<i32>) -> Result<String, Box<dyn Error>> {
-    let right = sum_vec_into_countmap(right);
-    let sum: i32 = left
+fn solve_part_one(data: ProcessedInput) -> Result<Output> {
+    let (left, right) = data;
+    Ok(stats::sorted_distances(&left, &right)
         .into_iter()
-        .map(|x| x * right.get(&x).unwrap_or(&0))
-        .sum();
-    Ok(sum.to_string())
+        .map(Output::from)
+        .sum())
 }
 
-fn sum_vec_into_countmap(right: Vec<i32>) -> HashMap<i32, i32> {
-    let mut count_map: HashMap<i32, i32> = HashMap::new();
+fn solve_part_two(data: ProcessedInput) -> Result<Output> {
+    let (left, right) = data;
+    Ok(stats::similarity_count_map(&left, &right))
+}
 
-    for &num in &right {
-        *count_map.entry(num).or_insert(0) += 1;
+fn print_stats(data: &ProcessedInput) {
+    let (left, right) = data;
+    let distances = stats::sorted_distances(left, right);
+    let Some(&max) = distances.last() else {
+        println!("No locations to analyse");
+        return;
+    };
+
+    let bucket_width = (max / 20).max(1);
+    println!("Distance histogram (bucket width {bucket_width}):");
+    for (start, count) in stats::histogram(&distances, bucket_width) {
+        println!(
+            "{:>10} | {:<6} {}",
+            start,
+            count,
+            "#".repeat(count * 50 / distances.len())
+        );
+    }
+    for percent in [50.0, 90.0, 99.0] {
+        if let Some(distance) = stats::percentile(&distances, percent) {
+            println!("p{percent}: {distance}");
+        }
     }
-    count_map
-}
 
-fn solve_part_one(mut left: Vec<i32>, mut right: Vec<i32>) -> Result<String, Box<dyn Error>> {
-    left.sort();
-    right.sort();
-    let sum: u32 = left
-        .into_iter()
-        .zip(right)
-        .map(|(l, r)| l.abs_diff(r))
-        .sum();
-    Ok(sum.to_string())
+    let count_map_start = Instant::now();
+    let count_map = stats::similarity_count_map(left, right);
+    let time_count_map = count_map_start.elapsed();
+    let merge_start = Instant::now();
+    let merge = stats::similarity_sorted_merge(left, right);
+    let time_merge = merge_start.elapsed();
+    println!(
+        "Similarity (count map): {} (Took {:?})",
+        count_map,
+        format_duration(time_count_map).to_string()
+    );
+    println!(
+        "Similarity (sorted merge): {} (Took {:?})",
+        merge,
+        format_duration(time_merge).to_string()
+    );
 }
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+
+/// The distance between each pair of locations once both lists are sorted,
+/// itself in ascending order.
+pub fn sorted_distances(left: &[i32], right: &[i32]) -> Vec<u32> {
+    let mut left = left.to_vec();
+    let mut right = right.to_vec();
+    left.sort_unstable();
+    right.sort_unstable();
+    let mut distances: Vec<u32> = left
+        .into_iter()
+        .zip(right)
+        .map(|(l, r)| l.abs_diff(r))
+        .collect();
+    distances.sort_unstable();
+    distances
+}
+
+/// Counts the distances falling in each bucket of `bucket_width`, returning
+/// the start of every bucket that has at least one distance in it.
+pub fn histogram(sorted_distances: &[u32], bucket_width: u32) -> Vec<(u32, usize)> {
+    let mut buckets: Vec<(u32, usize)> = vec![];
+    for &distance in sorted_distances {
+        let start = distance - distance % bucket_width;
+        match buckets.last_mut() {
+            Some((last, count)) if *last == start => *count += 1,
+            _ => buckets.push((start, 1)),
+        }
+    }
+    buckets
+}
+
+/// Nearest-rank percentile, so the result is always one of the distances.
+pub fn percentile(sorted_distances: &[u32], percent: f64) -> Option<u32> {
+    if sorted_distances.is_empty() {
+        return None;
+    }
+    let rank = (percent / 100.0 * sorted_distances.len() as f64).ceil() as usize;
+    Some(sorted_distances[rank.clamp(1, sorted_distances.len()) - 1])
+}
+
+/// Similarity score using a count of every number in the right list.
+pub fn similarity_count_map(left: &[i32], right: &[i32]) -> i64 {
+    let mut count_map: HashMap<i32, i64> = HashMap::new();
+    for &num in right {
+        *count_map.entry(num).or_insert(0) += 1;
+    }
+    left.iter()
+        .map(|&x| x as i64 * count_map.get(&x).unwrap_or(&0))
+        .sum()
+}
+
+/// Similarity score found by walking both sorted lists together, so each run
+/// of equal numbers on the left is matched against the run on the right.
+pub fn similarity_sorted_merge(left: &[i32], right: &[i32]) -> i64 {
+    let mut left = left.to_vec();
+    let mut right = right.to_vec();
+    left.sort_unstable();
+    right.sort_unstable();
+    let (mut l, mut r) = (0, 0);
+    let mut score = 0;
+    while l < left.len() && r < right.len() {
+        match left[l].cmp(&right[r]) {
+            std::cmp::Ordering::Less => l += 1,
+            std::cmp::Ordering::Greater => r += 1,
+            std::cmp::Ordering::Equal => {
+                let value = left[l];
+                let left_run = left[l..].iter().take_while(|&&x| x == value).count();
+                let right_run = right[r..].iter().take_while(|&&x| x == value).count();
+                score += value as i64 * left_run as i64 * right_run as i64;
+                l += left_run;
+                r += right_run;
+            }
+        }
+    }
+    score
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{process_input, solve_part_one, solve_part_two};
+
+    const EXAMPLE: &str = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+
+    #[test]
+    fn solves_the_example() {
+        let data = process_input(EXAMPLE.to_string()).unwrap();
+        assert_eq!(solve_part_one(data.clone()).unwrap(), 11);
+        assert_eq!(solve_part_two(data.clone()).unwrap(), 31);
+        let (left, right) = data;
+        assert_eq!(sorted_distances(&left, &right), vec![0, 1, 1, 2, 2, 5]);
+    }
+
+    #[test]
+    fn similarity_methods_agree() {
+        let left = [3, 3, 3, 1, 7, 7, -2];
+        let right = [3, 7, 3, 7, 7, 0, -2, -2];
+        // 3 * 3 * 2 + 7 * 2 * 3 - 2 * 1 * 2
+        assert_eq!(similarity_count_map(&left, &right), 56);
+        assert_eq!(similarity_sorted_merge(&left, &right), 56);
+
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..100 {
+            let mut list = |len| -> Vec<i32> { (0..len).map(|_| rng.gen_range(0..10)).collect() };
+            let (left, right) = (list(30), list(25));
+            assert_eq!(
+                similarity_sorted_merge(&left, &right),
+                similarity_count_map(&left, &right)
+            );
+        }
+        assert_eq!(similarity_sorted_merge(&[], &[1, 2]), 0);
+    }
+
+    #[test]
+    fn percentiles_use_the_nearest_rank() {
+        let distances = [1, 2, 3, 4];
+        assert_eq!(percentile(&distances, 0.0), Some(1));
+        assert_eq!(percentile(&distances, 100.0), Some(4));
+        // An even count takes the lower of the middle two.
+        assert_eq!(percentile(&distances, 50.0), Some(2));
+        assert_eq!(percentile(&distances, 51.0), Some(3));
+        assert_eq!(percentile(&[7], 99.0), Some(7));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+
+    #[test]
+    fn buckets_distances_by_their_start() {
+        let distances = [0, 4, 5, 9, 10, 23];
+        assert_eq!(
+            histogram(&distances, 5),
+            vec![(0, 2), (5, 2), (10, 1), (20, 1)]
+        );
+        assert_eq!(histogram(&distances, 1).len(), distances.len());
+        assert_eq!(histogram(&[], 5), vec![]);
+    }
+}
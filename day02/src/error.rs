@@ -0,0 +1,113 @@
+use std::{error::Error, fmt::Display, io};
+
+/// Everything that can go wrong running the puzzle. Line and column
+/// numbers are 1-based so they can be matched up with an editor.
+#[derive(Debug)]
+pub enum AocError {
+    Io(io::Error),
+    Argument(String),
+    ParseError {
+        line: usize,
+        column: usize,
+        token: String,
+    },
+    EmptyReport {
+        line: usize,
+    },
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AocError::Io(e) => write!(f, "Failed to read input: {e}"),
+            AocError::Argument(message) => f.write_str(message),
+            AocError::ParseError {
+                line,
+                column,
+                token,
+            } => write!(
+                f,
+                "Failed to parse {token:?} as a level at line {line}, column {column}"
+            ),
+            AocError::EmptyReport { line } => {
+                write!(f, "Report on line {line} must contain at least one number")
+            }
+        }
+    }
+}
+
+impl Error for AocError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AocError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for AocError {
+    fn from(e: io::Error) -> Self {
+        AocError::Io(e)
+    }
+}
+
+impl AocError {
+    /// Formats the error along with the input line it came from, underlining
+    /// the offending token where there is one.
+    pub fn render(&self, input: &str) -> String {
+        let (line, column, width) = match self {
+            AocError::ParseError {
+                line,
+                column,
+                token,
+            } => (*line, *column, token.chars().count()),
+            AocError::EmptyReport { line } => (*line, 1, 0),
+            _ => return format!("error: {self}"),
+        };
+        let source = input.lines().nth(line - 1).unwrap_or_default();
+        let gutter = " ".repeat(line.to_string().len());
+        let marker = format!("{}{}", " ".repeat(column - 1), "^".repeat(width.max(1)));
+        format!(
+            "error: {self}\n{gutter}--> input.txt:{line}:{column}\n{gutter} |\n{line} | {source}\n{gutter} | {marker}"
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn underlines_the_bad_token() {
+        let input = "1 2 3\n  4 x5 6\n";
+        let error = AocError::ParseError {
+            line: 2,
+            column: 5,
+            token: "x5".to_string(),
+        };
+        assert_eq!(
+            error.render(input),
+            "error: Failed to parse \"x5\" as a level at line 2, column 5
+ --> input.txt:2:5
+  |
+2 |   4 x5 6
+  |     ^^"
+        );
+    }
+
+    #[test]
+    fn widens_the_gutter_for_long_line_numbers() {
+        let input = "1\n".repeat(11) + "\n";
+        let rendered = AocError::EmptyReport { line: 12 }.render(&input);
+        assert_eq!(
+            rendered,
+            "error: Report on line 12 must contain at least one number\n  --> input.txt:12:1\n   |\n12 | \n   | ^"
+        );
+    }
+
+    #[test]
+    fn other_errors_have_no_source_line() {
+        let error = AocError::Argument("Unknown argument --x".to_string());
+        assert_eq!(error.render(""), "error: Unknown argument --x");
+    }
+}
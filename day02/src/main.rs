@@ -1,10 +1,31 @@
-use std::{cmp, error::Error, fs::read_to_string, time::Instant};
+use error::AocError;
+use safety::{Report, SafetyRules};
+use std::{env, fs::read_to_string, process::ExitCode, time::Instant};
+mod error;
+mod safety;
 
-type ProcessedInput = Vec<Vec<i32>>;
+type ProcessedInput = Vec<Report>;
 type Output = i32;
 
-fn main() -> Result<(), Box<dyn Error>> {
-    let input = read_to_string("input.txt")?;
+fn main() -> ExitCode {
+    let input = match read_to_string("input.txt") {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("{}", AocError::from(e).render(""));
+            return ExitCode::FAILURE;
+        }
+    };
+    match run(&input) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e.render(&input));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(input: &str) -> Result<(), AocError> {
+    let args = Args::parse(env::args().skip(1))?;
     println!("Lines in Input: {}", input.len());
     let time_start = Instant::now();
 
@@ -14,7 +35,7 @@ fn main() -> Result<(), Box<dyn Error>> {
     let part_one = solve_part_one(processed.clone());
     let time_one = time_start.elapsed();
 
-    let part_two = solve_part_two(processed);
+    let part_two = solve_part_two(processed.clone());
     let time_two = time_start.elapsed();
 
     println!(
@@ -31,96 +52,147 @@ fn main() -> Result<(), Box<dyn Error>> {
         part_two,
         time_two.as_millis()
     );
-    Ok(())
-}
+    for error in [&part_one, &part_two]
+        .into_iter()
+        .filter_map(|p| p.as_ref().err())
+    {
+        eprintln!("{}", error.render(input));
+    }
 
-fn solve_part_two(processed: ProcessedInput) -> Result<Output, Box<dyn Error>> {
-    let mut count = 0;
-    for report in processed {
-        if is_safe_report_dampened(report)? {
-            count += 1;
+    if let Some(rules) = args.rules {
+        let mut safe = 0;
+        for report in &processed {
+            let Some(removed) = rules.check(report)? else {
+                continue;
+            };
+            safe += 1;
+            if args.explain && !removed.is_empty() {
+                println!(
+                    "Line {}: safe after removing indices {:?}",
+                    report.line, removed
+                );
+            }
         }
+        println!("Custom {:?}: {}", rules, safe);
     }
-    Ok(count)
+    Ok(())
 }
 
-fn is_safe_report_dampened(report: Vec<i32>) -> Result<bool, Box<dyn Error>> {
-    for delete_index in 0..report.len() {
-        let mut clone = report.clone();
-        clone.remove(delete_index);
-        if is_safe_report(clone)? {
-            return Ok(true);
-        }
-    }
-    Ok(false)
+/// Usage: day02 [--min-step N] [--max-step N] [--dampener K] [--explain]
+/// Any of the rule flags runs an extra count with those rules, and --explain
+/// lists which levels were removed to make each report safe.
+struct Args {
+    rules: Option<SafetyRules>,
+    explain: bool,
 }
 
-fn process_input(input: String) -> Result<ProcessedInput, Box<dyn Error>> {
-    let lines = input.lines();
-    let mut processed: ProcessedInput = Vec::new();
-    for line in lines {
-        let mut levels = Vec::new();
-        let numbers = line.split_whitespace();
-        for number in numbers {
-            levels.push(number.parse()?)
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, AocError> {
+        let mut rules: Option<SafetyRules> = None;
+        let mut explain = false;
+        while let Some(flag) = args.next() {
+            if flag == "--explain" {
+                explain = true;
+                continue;
+            }
+            let value = args.next().ok_or(AocError::Argument(format!(
+                "Missing value for argument {flag}"
+            )))?;
+            let invalid = |_| AocError::Argument(format!("Invalid value {value} for {flag}"));
+            let rules = rules.get_or_insert_with(SafetyRules::default);
+            match flag.as_str() {
+                "--min-step" => rules.min_step = value.parse().map_err(invalid)?,
+                "--max-step" => rules.max_step = value.parse().map_err(invalid)?,
+                "--dampener" => rules.dampener = value.parse().map_err(invalid)?,
+                _ => return Err(AocError::Argument(format!("Unknown argument {flag}"))),
+            }
         }
-        processed.push(levels);
+        if explain && rules.is_none() {
+            rules = Some(SafetyRules {
+                dampener: 1,
+                ..SafetyRules::default()
+            });
+        }
+        Ok(Args { rules, explain })
     }
-    Ok(processed)
 }
 
-fn solve_part_one(input: ProcessedInput) -> Result<Output, Box<dyn Error>> {
+fn solve_part_two(processed: ProcessedInput) -> Result<Output, AocError> {
+    let rules = SafetyRules {
+        dampener: 1,
+        ..SafetyRules::default()
+    };
+    count_safe(&processed, &rules)
+}
+
+fn count_safe(processed: &ProcessedInput, rules: &SafetyRules) -> Result<Output, AocError> {
     let mut count = 0;
-    for report in input {
-        if is_safe_report(report)? {
+    for report in processed {
+        if rules.check(report)?.is_some() {
             count += 1;
         }
     }
     Ok(count)
 }
 
-fn is_safe_report(report: Vec<i32>) -> Result<bool, Box<dyn Error>> {
-    let direction = get_direction(report.clone()).ok_or("Report must have at least one element")?;
-    let mut iter = report.into_iter();
-    let mut previous = iter
-        .next()
-        .ok_or("Report must contain at least one number")?;
-    for item in iter {
-        let near_direction = compare_directions(previous, item);
-        if direction != near_direction {
-            return Ok(false);
-        }
-        if !valid_neighbours(item, previous) {
-            return Ok(false);
+fn process_input(input: &str) -> Result<ProcessedInput, AocError> {
+    let mut processed: ProcessedInput = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let mut levels = Vec::new();
+        for number in line.split_whitespace() {
+            let offset = number.as_ptr() as usize - line.as_ptr() as usize;
+            let level = number.parse().map_err(|_| AocError::ParseError {
+                line: index + 1,
+                column: line[..offset].chars().count() + 1,
+                token: number.to_owned(),
+            })?;
+            levels.push(level);
         }
-        previous = item;
+        processed.push(Report {
+            line: index + 1,
+            levels,
+        });
     }
-    Ok(true)
+    Ok(processed)
 }
 
-fn valid_neighbours(item: i32, previous: i32) -> bool {
-    let delta = item.abs_diff(previous);
-    if delta < 1 {
-        return false;
-    }
-    if delta > 3 {
-        return false;
-    }
-    true
-}
-#[derive(cmp::PartialEq)]
-enum Direction {
-    Increasing,
-    Decreasing,
-}
-fn get_direction(report: Vec<i32>) -> Option<Direction> {
-    Some(compare_directions(*report.first()?, *report.last()?))
+fn solve_part_one(input: ProcessedInput) -> Result<Output, AocError> {
+    count_safe(&input, &SafetyRules::default())
 }
 
-fn compare_directions(left: i32, right: i32) -> Direction {
-    let direction = left - right;
-    match direction > 0 {
-        true => Direction::Decreasing,
-        false => Direction::Increasing,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_where_a_level_failed_to_parse() {
+        let error = process_input("1 2 3\n  4 x5 6\n").unwrap_err();
+        let AocError::ParseError {
+            line,
+            column,
+            token,
+        } = error
+        else {
+            panic!("Expected a parse error, got {error:?}");
+        };
+        assert_eq!((line, column, token.as_str()), (2, 5, "x5"));
+
+        let error = process_input("7 -").unwrap_err();
+        assert!(matches!(
+            error,
+            AocError::ParseError {
+                line: 1,
+                column: 3,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn empty_lines_are_empty_reports() {
+        let processed = process_input("1 2 3\n\n4 5 6\n").unwrap();
+        assert_eq!(processed[1].levels, vec![]);
+        let error = solve_part_one(processed).unwrap_err();
+        assert!(matches!(error, AocError::EmptyReport { line: 2 }));
     }
 }
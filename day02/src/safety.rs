@@ -0,0 +1,173 @@
+use crate::error::AocError;
+
+/// A line of levels from the input, along with its 1-based line number.
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub line: usize,
+    pub levels: Vec<i32>,
+}
+
+/// What makes a report safe: every step between neighbouring levels moves in
+/// the same direction by between `min_step` and `max_step`, once up to
+/// `dampener` levels have been removed.
+#[derive(Clone, Debug)]
+pub struct SafetyRules {
+    pub min_step: i32,
+    pub max_step: i32,
+    pub dampener: usize,
+}
+
+impl Default for SafetyRules {
+    fn default() -> Self {
+        Self {
+            min_step: 1,
+            max_step: 3,
+            dampener: 0,
+        }
+    }
+}
+
+impl SafetyRules {
+    /// Returns the indices that have to be removed to make the report safe,
+    /// which is empty if it already is, or None if the dampener can't save it.
+    /// The fewest possible removals are returned.
+    pub fn check(&self, report: &Report) -> Result<Option<Vec<usize>>, AocError> {
+        if report.levels.is_empty() {
+            return Err(AocError::EmptyReport { line: report.line });
+        }
+        let report = &report.levels;
+        let increasing = self.fewest_removals(report, |prev, next| next - prev);
+        let decreasing = self.fewest_removals(report, |prev, next| prev - next);
+        let best = match (increasing, decreasing) {
+            (Some(i), Some(d)) => Some(if d.len() < i.len() { d } else { i }),
+            (i, d) => i.or(d),
+        };
+        Ok(best)
+    }
+
+    /// Dynamic programme over the report where `removals[i]` is the fewest
+    /// levels removed before `i` so that the kept levels up to and including
+    /// `i` are safe. A kept level can only follow one of the `dampener + 1`
+    /// levels before it, so this is O(n * dampener). A dampener beyond the
+    /// length of the report can't remove any more, so it's clamped to it.
+    fn fewest_removals(
+        &self,
+        report: &[i32],
+        step: impl Fn(i32, i32) -> i32,
+    ) -> Option<Vec<usize>> {
+        let n = report.len();
+        let dampener = self.dampener.min(n);
+        let mut removals = vec![usize::MAX; n];
+        let mut previous_kept: Vec<Option<usize>> = vec![None; n];
+        for i in 0..n {
+            if i <= dampener {
+                removals[i] = i;
+            }
+            for j in i.saturating_sub(dampener + 1)..i {
+                if removals[j] == usize::MAX {
+                    continue;
+                }
+                let delta = step(report[j], report[i]);
+                if delta < self.min_step || delta > self.max_step {
+                    continue;
+                }
+                let total = removals[j] + (i - j - 1);
+                if total < removals[i] {
+                    removals[i] = total;
+                    previous_kept[i] = Some(j);
+                }
+            }
+        }
+
+        let (last, _) = (0..n)
+            .filter(|&i| removals[i] != usize::MAX)
+            .map(|i| (i, removals[i] + (n - 1 - i)))
+            .filter(|&(_, total)| total <= dampener)
+            .min_by_key(|&(_, total)| total)?;
+
+        let mut kept = vec![false; n];
+        let mut current = Some(last);
+        while let Some(i) = current {
+            kept[i] = true;
+            current = previous_kept[i];
+        }
+        Some((0..n).filter(|&i| !kept[i]).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: [&[i32]; 6] = [
+        &[7, 6, 4, 2, 1],
+        &[1, 2, 7, 8, 9],
+        &[9, 7, 6, 2, 1],
+        &[1, 3, 2, 4, 5],
+        &[8, 6, 4, 4, 1],
+        &[1, 3, 6, 7, 9],
+    ];
+
+    fn check(levels: &[i32], dampener: usize) -> Option<Vec<usize>> {
+        let rules = SafetyRules {
+            dampener,
+            ..Default::default()
+        };
+        let report = Report {
+            line: 1,
+            levels: levels.to_vec(),
+        };
+        rules.check(&report).unwrap()
+    }
+
+    #[test]
+    fn example_without_dampener() {
+        let checked: Vec<_> = EXAMPLE.iter().map(|levels| check(levels, 0)).collect();
+        assert_eq!(
+            checked,
+            [Some(vec![]), None, None, None, None, Some(vec![])]
+        );
+    }
+
+    #[test]
+    fn example_with_dampener() {
+        let checked: Vec<_> = EXAMPLE.iter().map(|levels| check(levels, 1)).collect();
+        assert_eq!(
+            checked,
+            [
+                Some(vec![]),
+                None,
+                None,
+                Some(vec![2]),
+                Some(vec![3]),
+                Some(vec![])
+            ]
+        );
+    }
+
+    #[test]
+    fn removes_the_fewest_levels() {
+        assert_eq!(check(&[1, 5, 9, 2, 3, 4], 1), None);
+        assert_eq!(check(&[1, 5, 9, 2, 3, 4], 2), Some(vec![1, 2]));
+        assert_eq!(check(&[1, 5, 9, 2, 3, 4], 3), Some(vec![1, 2]));
+        // The first and last levels can be removed too.
+        assert_eq!(check(&[9, 1, 2, 3, 0], 2), Some(vec![0, 4]));
+        assert_eq!(check(&[5, 4, 3, 9, 8, 2], 2), Some(vec![3, 4]));
+    }
+
+    #[test]
+    fn clamps_huge_dampeners() {
+        assert_eq!(check(&[1, 5, 9, 2, 3, 4], usize::MAX), Some(vec![1, 2]));
+        assert_eq!(check(&[4, 4, 4], usize::MAX), Some(vec![1, 2]));
+        assert_eq!(check(&[4], usize::MAX), Some(vec![]));
+    }
+
+    #[test]
+    fn rejects_empty_reports() {
+        let report = Report {
+            line: 3,
+            levels: vec![],
+        };
+        assert!(SafetyRules::default().check(&report).is_err());
+    }
+}
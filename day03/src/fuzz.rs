@@ -0,0 +1,87 @@
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::{oracle, process_input, solve_part_two};
+
+/// Pieces that random corrupted memory is stitched together from. Partial
+/// instructions are included so that instructions get split and rejoined.
+const FRAGMENTS: &[&str] = &[
+    "mul(", "mul", "mu", "l(", "(", ")", ",", "do()", "don't()", "do(", "don't", "do", "n't()",
+    "1", "23", "456", "7", "x", " ", "\n", "!", "?", "what()",
+];
+
+/// An input the interpreter and the golf.py oracle give different totals for.
+#[derive(Debug)]
+pub struct Disagreement {
+    pub memory: String,
+    pub interpreter: Option<i64>,
+    pub oracle: i64,
+}
+
+impl Disagreement {
+    /// Whether the muls the oracle joins across disabled spans account for
+    /// the whole difference between the two totals.
+    pub fn is_joined_mul(&self) -> bool {
+        self.interpreter == Some(self.oracle - oracle::joined_total(&self.memory))
+    }
+}
+
+/// Runs the interpreter and the golf.py oracle over random corrupted memory,
+/// returning every input the two disagree on.
+pub fn cross_check(runs: usize, seed: u64) -> Vec<Disagreement> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut disagreements = vec![];
+    for _ in 0..runs {
+        let length = rng.gen_range(0..40);
+        let memory: String = (0..length).map(|_| fragment(&mut rng)).collect();
+        let oracle = oracle::solve_part_two(&memory);
+        let interpreter = process_input(&memory).and_then(solve_part_two).ok();
+        if interpreter != Some(oracle) {
+            disagreements.push(Disagreement {
+                memory,
+                interpreter,
+                oracle,
+            });
+        }
+    }
+    disagreements
+}
+
+/// Usually a plain fragment, but sometimes a `mul` with a disabled span in
+/// the middle of its arguments, which single fragments almost never line up
+/// to make.
+fn fragment(rng: &mut StdRng) -> String {
+    if !rng.gen_bool(0.02) {
+        return FRAGMENTS.choose(rng).unwrap().to_string();
+    }
+    let mut pieces = ["mul(", "1", ",", "23", ")"].map(String::from);
+    let cut = rng.gen_range(1..pieces.len());
+    let filler: String = (0..rng.gen_range(0..3))
+        .map(|_| *FRAGMENTS.choose(rng).unwrap())
+        .collect();
+    pieces[cut] = format!("don't(){filler}do(){}", pieces[cut]);
+    pieces.concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disagreements_are_only_joined_muls() {
+        let disagreements = cross_check(20_000, 7);
+        assert!(
+            !disagreements.is_empty(),
+            "the fuzzer should find the joined mul disagreement"
+        );
+        for disagreement in disagreements {
+            assert!(disagreement.is_joined_mul(), "{disagreement:?}");
+        }
+    }
+
+    #[test]
+    fn joined_mul_regression() {
+        let memory = "mul(1,don't()do()2)";
+        assert_eq!(process_input(memory).and_then(solve_part_two).unwrap(), 0);
+        assert_eq!(oracle::solve_part_two(memory), 2);
+    }
+}
@@ -0,0 +1,200 @@
+/// An instruction recognised in the corrupted memory.
+#[derive(Clone, Copy, Debug)]
+pub enum Instruction {
+    Mul(i64, i64),
+    Do,
+    Dont,
+    Add(i64, i64),
+    Neg(i64),
+}
+
+/// How to recognise one kind of instruction: `name(arg,arg,...)` with exactly
+/// `arity` unsigned integer arguments and nothing else between the brackets.
+pub struct InstructionSpec {
+    pub name: &'static str,
+    pub arity: usize,
+    pub build: fn(&[i64]) -> Instruction,
+}
+
+const MUL: InstructionSpec = InstructionSpec {
+    name: "mul",
+    arity: 2,
+    build: |args| Instruction::Mul(args[0], args[1]),
+};
+const DO: InstructionSpec = InstructionSpec {
+    name: "do",
+    arity: 0,
+    build: |_| Instruction::Do,
+};
+const DONT: InstructionSpec = InstructionSpec {
+    name: "don't",
+    arity: 0,
+    build: |_| Instruction::Dont,
+};
+const ADD: InstructionSpec = InstructionSpec {
+    name: "add",
+    arity: 2,
+    build: |args| Instruction::Add(args[0], args[1]),
+};
+const NEG: InstructionSpec = InstructionSpec {
+    name: "neg",
+    arity: 1,
+    build: |args| Instruction::Neg(args[0]),
+};
+
+/// The instructions from the puzzle.
+pub const STANDARD: &[InstructionSpec] = &[MUL, DO, DONT];
+/// The puzzle's instructions plus some extras of our own.
+pub const EXTENDED: &[InstructionSpec] = &[MUL, DO, DONT, ADD, NEG];
+
+#[derive(Clone, Copy, Debug)]
+pub struct Token {
+    /// Byte offset of the first character of the instruction.
+    pub offset: usize,
+    pub instruction: Instruction,
+}
+
+/// Scans the whole input byte by byte, yielding every well formed
+/// instruction in the table and skipping everything else.
+pub struct Tokenizer<'a> {
+    input: &'a [u8],
+    position: usize,
+    table: &'a [InstructionSpec],
+}
+
+impl<'a> Tokenizer<'a> {
+    pub fn new(input: &'a str, table: &'a [InstructionSpec]) -> Self {
+        Self {
+            input: input.as_bytes(),
+            position: 0,
+            table,
+        }
+    }
+
+    /// Tries to read `spec` at `start`, returning the instruction and the
+    /// offset just past its closing bracket.
+    fn read(&self, spec: &InstructionSpec, start: usize) -> Option<(Instruction, usize)> {
+        let mut pos = self.expect(start, spec.name.as_bytes())?;
+        pos = self.expect(pos, b"(")?;
+        let mut args = Vec::with_capacity(spec.arity);
+        for i in 0..spec.arity {
+            if i > 0 {
+                pos = self.expect(pos, b",")?;
+            }
+            let (arg, next) = self.number(pos)?;
+            args.push(arg);
+            pos = next;
+        }
+        pos = self.expect(pos, b")")?;
+        Some(((spec.build)(&args), pos))
+    }
+
+    fn expect(&self, pos: usize, bytes: &[u8]) -> Option<usize> {
+        self.input[pos..]
+            .starts_with(bytes)
+            .then_some(pos + bytes.len())
+    }
+
+    fn number(&self, start: usize) -> Option<(i64, usize)> {
+        let digits = self.input[start..]
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if digits == 0 {
+            return None;
+        }
+        let end = start + digits;
+        let number = std::str::from_utf8(&self.input[start..end]).ok()?;
+        Some((number.parse().ok()?, end))
+    }
+}
+
+impl Iterator for Tokenizer<'_> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.position < self.input.len() {
+            let start = self.position;
+            let found = self.table.iter().find_map(|spec| self.read(spec, start));
+            if let Some((instruction, end)) = found {
+                self.position = end;
+                return Some(Token {
+                    offset: start,
+                    instruction,
+                });
+            }
+            self.position += 1;
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(input: &str, table: &[InstructionSpec]) -> Vec<(usize, String)> {
+        Tokenizer::new(input, table)
+            .map(|t| (t.offset, format!("{:?}", t.instruction)))
+            .collect()
+    }
+
+    #[test]
+    fn finds_the_example_instructions() {
+        let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let found: Vec<String> = tokens(input, STANDARD)
+            .into_iter()
+            .map(|(_, i)| i)
+            .collect();
+        assert_eq!(
+            found,
+            [
+                "Mul(2, 4)",
+                "Dont",
+                "Mul(5, 5)",
+                "Mul(11, 8)",
+                "Do",
+                "Mul(8, 5)"
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_byte_offsets() {
+        let input = "xmul(2,4)\ndo()  don't()\nmul(10,1)";
+        let offsets: Vec<usize> = tokens(input, STANDARD)
+            .into_iter()
+            .map(|(o, _)| o)
+            .collect();
+        assert_eq!(offsets, [1, 10, 16, 24]);
+        assert!(input[24..].starts_with("mul(10,1)"));
+    }
+
+    #[test]
+    fn skips_partial_instructions() {
+        assert!(tokens("mul(1,2", STANDARD).is_empty());
+        assert!(tokens("mul(1,", STANDARD).is_empty());
+        assert!(tokens("don't(", STANDARD).is_empty());
+        assert!(tokens("mul( 1,2)mul(1,2,3)mul(-1,2)", STANDARD).is_empty());
+        // A broken instruction doesn't swallow a good one inside it.
+        assert_eq!(
+            tokens("mul(1,mul(2,3)", STANDARD),
+            [(6, "Mul(2, 3)".to_string())]
+        );
+    }
+
+    #[test]
+    fn extended_table_adds_instructions() {
+        let input = "add(2,3)neg(4)mul(2,2)";
+        assert_eq!(
+            tokens(input, EXTENDED),
+            [
+                (0, "Add(2, 3)".to_string()),
+                (8, "Neg(4)".to_string()),
+                (14, "Mul(2, 2)".to_string())
+            ]
+        );
+        assert_eq!(tokens(input, STANDARD), [(14, "Mul(2, 2)".to_string())]);
+        assert!(tokens("add(1)neg(1,2)", EXTENDED).is_empty());
+    }
+}
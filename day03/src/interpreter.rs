@@ -0,0 +1,80 @@
+use crate::instruction::{Instruction, Token};
+
+/// Runs instructions in order, keeping the running total and whether
+/// instructions are currently enabled by `do()`/`don't()`.
+pub struct Interpreter {
+    respect_conditionals: bool,
+    enabled: bool,
+    pub total: i64,
+    /// Every instruction that actually ran, in order.
+    pub executed: Vec<Token>,
+}
+
+impl Interpreter {
+    pub fn new(respect_conditionals: bool) -> Self {
+        Self {
+            respect_conditionals,
+            enabled: true,
+            total: 0,
+            executed: vec![],
+        }
+    }
+
+    pub fn run(&mut self, tokens: &[Token]) {
+        for token in tokens {
+            self.execute(*token);
+        }
+    }
+
+    fn execute(&mut self, token: Token) {
+        match token.instruction {
+            Instruction::Do => self.enabled = true,
+            Instruction::Dont => self.enabled = false,
+            _ if self.respect_conditionals && !self.enabled => return,
+            Instruction::Mul(l, r) => self.total += l * r,
+            Instruction::Add(l, r) => self.total += l + r,
+            Instruction::Neg(v) => self.total -= v,
+        }
+        self.executed.push(token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instruction::{Tokenizer, EXTENDED, STANDARD};
+
+    fn run(input: &str, extended: bool, respect_conditionals: bool) -> Interpreter {
+        let table = if extended { EXTENDED } else { STANDARD };
+        let tokens: Vec<Token> = Tokenizer::new(input, table).collect();
+        let mut interpreter = Interpreter::new(respect_conditionals);
+        interpreter.run(&tokens);
+        interpreter
+    }
+
+    #[test]
+    fn solves_the_examples() {
+        let one = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
+        assert_eq!(run(one, false, false).total, 161);
+        let two = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        assert_eq!(run(two, false, false).total, 161);
+        assert_eq!(run(two, false, true).total, 48);
+    }
+
+    #[test]
+    fn conditionals_carry_across_lines() {
+        let input = "mul(1,2)don't()\nmul(3,4)\nmul(5,6)do()\nmul(7,8)";
+        let interpreter = run(input, false, true);
+        assert_eq!(interpreter.total, 2 + 56);
+        let offsets: Vec<usize> = interpreter.executed.iter().map(|t| t.offset).collect();
+        assert_eq!(offsets, [0, 8, 33, 38]);
+    }
+
+    #[test]
+    fn runs_extended_instructions() {
+        let input = "add(2,3)neg(4)mul(2,2)don't()add(100,100)neg(100)do()neg(1)";
+        assert_eq!(run(input, true, true).total, 5 - 4 + 4 - 1);
+        assert_eq!(run(input, true, false).total, 5 - 4 + 4 + 200 - 100 - 1);
+        assert_eq!(run(input, false, true).total, 4);
+    }
+}
@@ -1,17 +1,41 @@
-use anyhow::Result;
+use anyhow::{anyhow, bail, Context, Result};
 use humantime::format_duration;
-use regex::Regex;
-use std::{fs::read_to_string, time::Instant};
+use instruction::{InstructionSpec, Token, Tokenizer, EXTENDED, STANDARD};
+use interpreter::Interpreter;
+use std::{env, fs::read_to_string, time::Instant};
+mod fuzz;
+mod instruction;
+mod interpreter;
+mod oracle;
 
-type ProcessedInput = Vec<Operation>;
-type Output = i32;
+type ProcessedInput = Vec<Token>;
+type Output = i64;
 
 fn main() -> Result<()> {
+    let args = Args::parse(env::args().skip(1))?;
+    if let Some(runs) = args.fuzz_runs {
+        let disagreements = fuzz::cross_check(runs, args.seed);
+        let (joined, unexplained): (Vec<_>, Vec<_>) =
+            disagreements.iter().partition(|d| d.is_joined_mul());
+        for d in &unexplained {
+            println!(
+                "Disagreement on {:?}: interpreter {:?}, oracle {}",
+                d.memory, d.interpreter, d.oracle
+            );
+        }
+        println!(
+            "{} disagreements in {runs} runs (seed {}), {} of them muls joined by the oracle",
+            disagreements.len(),
+            args.seed,
+            joined.len()
+        );
+        return Ok(());
+    }
     let input = read_to_string("input.txt")?;
     println!("Lines in Input: {}", input.len());
 
     let processing_start = Instant::now();
-    let processed = process_input(input)?;
+    let processed = process_input(&input)?;
     let time_processing = processing_start.elapsed();
 
     let part_one = solve_part_one(processed.clone());
@@ -19,7 +43,7 @@ fn main() -> Result<()> {
     let time_one = part_one_start.elapsed();
 
     let part_two_start = Instant::now();
-    let part_two = solve_part_two(processed);
+    let part_two = solve_part_two(processed.clone());
     let time_two = part_two_start.elapsed();
 
     println!(
@@ -36,68 +60,81 @@ fn main() -> Result<()> {
         part_two,
         format_duration(time_two).to_string()
     );
+
+    if args.extended {
+        let mut interpreter = Interpreter::new(true);
+        interpreter.run(&tokenize(&input, EXTENDED));
+        println!("Extended: {}", interpreter.total);
+    }
+    if args.trace {
+        let mut interpreter = Interpreter::new(true);
+        interpreter.run(&processed);
+        for token in interpreter.executed {
+            println!("{:>8}: {:?}", token.offset, token.instruction);
+        }
+    }
     Ok(())
 }
 
-#[derive(Clone)]
-enum Operation {
-    Mul((i32, i32)),
-    Do(),
-    Dont(),
-}
-fn process_input(input: String) -> Result<ProcessedInput> {
-    let mut vec = Vec::new();
-    input
-        .lines()
-        .filter(|x| !x.is_empty())
-        .for_each(|x| vec.append(&mut process_line(x)));
-    Ok(vec)
+/// Usage: day03 [--extended] [--trace] [--fuzz RUNS [--seed SEED]]
+/// --extended also runs the input with the extra instructions enabled,
+/// --trace prints the byte offset of every instruction part two executes, and
+/// --fuzz cross-checks part two against the golf.py oracle on random input,
+/// printing any disagreement other than a mul the oracle joins across a span.
+struct Args {
+    extended: bool,
+    trace: bool,
+    fuzz_runs: Option<usize>,
+    seed: u64,
 }
 
-fn process_line(line: &str) -> Vec<Operation> {
-    let regex = Regex::new(r"(mul\(([0-9]+),([0-9]+)\))|(do\(()()\))|(don't\(()()\))").unwrap(); // Matches "mul(454, 123)" or similar
-    let mut operations: Vec<Operation> = vec![];
-    for (_, [op_str, l, r]) in regex.captures_iter(line).map(|c| c.extract()) {
-        if op_str.starts_with("mul") {
-            operations.push(Operation::Mul((l.parse().unwrap(), r.parse().unwrap())));
-        } else {
-            match op_str {
-                r"do()" => operations.push(Operation::Do()),
-                r"don't()" => operations.push(Operation::Dont()),
-                _ => continue,
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Args {
+            extended: false,
+            trace: false,
+            fuzz_runs: None,
+            seed: 0,
+        };
+        while let Some(flag) = args.next() {
+            match flag.as_str() {
+                "--extended" => parsed.extended = true,
+                "--trace" => parsed.trace = true,
+                "--fuzz" => parsed.fuzz_runs = Some(parse_value(&flag, args.next())?),
+                "--seed" => parsed.seed = parse_value(&flag, args.next())?,
+                _ => bail!("Unknown argument {flag}"),
             }
         }
+        Ok(parsed)
     }
-    operations
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T>
+where
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let value = value.ok_or(anyhow!("Missing value for argument {flag}"))?;
+    value
+        .parse()
+        .with_context(|| format!("Invalid value {value} for argument {flag}"))
+}
+
+fn process_input(input: &str) -> Result<ProcessedInput> {
+    Ok(tokenize(input, STANDARD))
+}
+
+fn tokenize(input: &str, table: &[InstructionSpec]) -> Vec<Token> {
+    Tokenizer::new(input, table).collect()
 }
 
 fn solve_part_one(data: ProcessedInput) -> Result<Output> {
-    let sum = data
-        .into_iter()
-        .filter_map(|op| {
-            if let Operation::Mul((l, r)) = op {
-                Some(l * r)
-            } else {
-                None
-            }
-        })
-        .sum();
-    Ok(sum)
+    let mut interpreter = Interpreter::new(false);
+    interpreter.run(&data);
+    Ok(interpreter.total)
 }
 
 fn solve_part_two(data: ProcessedInput) -> Result<Output> {
-    let mut active = true;
-    let mut count = 0;
-    for operation in data {
-        match operation {
-            Operation::Mul((l, r)) => {
-                if active {
-                    count += l * r
-                }
-            }
-            Operation::Do() => active = true,
-            Operation::Dont() => active = false,
-        }
-    }
-    Ok(count)
+    let mut interpreter = Interpreter::new(true);
+    interpreter.run(&data);
+    Ok(interpreter.total)
 }
@@ -0,0 +1,106 @@
+//! A port of golf.py, kept as an independent check on the interpreter.
+//! Rather than tracking whether instructions are enabled, it deletes every
+//! `don't()`...`do()` span from the memory and then sums whatever `mul`s
+//! are left. Stripping can join the text either side of a span into a new
+//! `mul`, which the interpreter never sees, so that is one kind of
+//! disagreement the fuzzer turns up.
+
+use std::ops::Range;
+
+pub fn solve_part_two(memory: &str) -> i64 {
+    let (stripped, _) = strip_disabled_spans(&with_closing_do(memory));
+    muls(&stripped).map(|(product, _)| product).sum()
+}
+
+/// The sum of the `mul`s that stripping spans out of `memory` makes by
+/// joining text from either side of a span, which the interpreter never
+/// sees. The oracle's total is more than the interpreter's by exactly this.
+pub fn joined_total(memory: &str) -> i64 {
+    let (stripped, splices) = strip_disabled_spans(&with_closing_do(memory));
+    let total = muls(&stripped)
+        .filter(|(_, range)| {
+            splices
+                .iter()
+                .any(|&splice| range.start < splice && splice < range.end)
+        })
+        .map(|(product, _)| product)
+        .sum();
+    total
+}
+
+/// golf.py appends a do() so a trailing don't() still closes its span.
+fn with_closing_do(memory: &str) -> String {
+    format!("{memory}do()")
+}
+
+/// Removes each `don't()` up to and including the nearest `do()` after it,
+/// like the non-greedy `re.sub` in golf.py. Also returns where in the
+/// stripped text each span was cut out.
+fn strip_disabled_spans(memory: &str) -> (String, Vec<usize>) {
+    let mut stripped = String::with_capacity(memory.len());
+    let mut splices = vec![];
+    let mut rest = memory;
+    while let Some(start) = rest.find("don't()") {
+        stripped.push_str(&rest[..start]);
+        splices.push(stripped.len());
+        let span = &rest[start..];
+        match span.find("do()") {
+            Some(end) => rest = &span[end + "do()".len()..],
+            None => {
+                rest = span;
+                break;
+            }
+        }
+    }
+    stripped.push_str(rest);
+    (stripped, splices)
+}
+
+/// Every `mul(a,b)` where a and b are runs of digits, with its product and
+/// where it sits in `memory`.
+fn muls(memory: &str) -> impl Iterator<Item = (i64, Range<usize>)> + '_ {
+    let mut position = 0;
+    std::iter::from_fn(move || {
+        while let Some(found) = memory[position..].find("mul(") {
+            let start = position + found;
+            position = start + "mul(".len();
+            if let Some((product, len)) = read_mul_args(&memory[position..]) {
+                position += len;
+                return Some((product, start..position));
+            }
+        }
+        None
+    })
+}
+
+fn read_mul_args(args: &str) -> Option<(i64, usize)> {
+    let close = args.find(')')?;
+    let (left, right) = args[..close].split_once(',')?;
+    let is_number = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    if !is_number(left) || !is_number(right) {
+        return None;
+    }
+    let product = left.parse::<i64>().ok()? * right.parse::<i64>().ok()?;
+    Some((product, close + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_disabled_spans() {
+        let example = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        assert_eq!(solve_part_two(example), 48);
+        assert_eq!(solve_part_two("mul(2,3)don't()mul(4,5)"), 6);
+        assert_eq!(joined_total(example), 0);
+    }
+
+    #[test]
+    fn spots_joined_muls() {
+        assert_eq!(solve_part_two("mul(1,don't()do()2)"), 2);
+        assert_eq!(joined_total("mul(1,don't()do()2)"), 2);
+        assert_eq!(joined_total("mudon't()xdo()l(3,4)mul(5,6)"), 12);
+        assert_eq!(joined_total("mul(1,2)don't()do()mul(3,4)"), 0);
+    }
+}
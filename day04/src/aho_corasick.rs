@@ -0,0 +1,168 @@
+use std::collections::{HashMap, VecDeque};
+
+const ROOT: usize = 0;
+
+#[derive(Default)]
+struct Node {
+    next: HashMap<char, usize>,
+    fail: usize,
+    /// Every pattern that ends at this node, including via failure links.
+    outputs: Vec<usize>,
+}
+
+/// An Aho-Corasick automaton over chars. Text is fed in one char at a time,
+/// and after each char the patterns ending there can be read off the state.
+pub struct AhoCorasick {
+    nodes: Vec<Node>,
+}
+
+impl AhoCorasick {
+    pub fn new(patterns: &[Vec<char>]) -> Self {
+        let mut nodes = vec![Node::default()];
+        for (id, pattern) in patterns.iter().enumerate() {
+            let mut state = ROOT;
+            for &c in pattern {
+                state = match nodes[state].next.get(&c) {
+                    Some(&next) => next,
+                    None => {
+                        nodes.push(Node::default());
+                        let next = nodes.len() - 1;
+                        nodes[state].next.insert(c, next);
+                        next
+                    }
+                };
+            }
+            nodes[state].outputs.push(id);
+        }
+
+        // Breadth first, so a node's failure target is always finished first.
+        let mut queue: VecDeque<usize> = nodes[ROOT].next.values().copied().collect();
+        while let Some(state) = queue.pop_front() {
+            let children: Vec<(char, usize)> =
+                nodes[state].next.iter().map(|(&c, &n)| (c, n)).collect();
+            for (c, child) in children {
+                let mut fallback = nodes[state].fail;
+                let fail = loop {
+                    if let Some(&n) = nodes[fallback].next.get(&c) {
+                        break n;
+                    }
+                    if fallback == ROOT {
+                        break ROOT;
+                    }
+                    fallback = nodes[fallback].fail;
+                };
+                nodes[child].fail = fail;
+                let inherited = nodes[fail].outputs.clone();
+                nodes[child].outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+        Self { nodes }
+    }
+
+    pub fn start(&self) -> usize {
+        ROOT
+    }
+
+    pub fn step(&self, mut state: usize, c: char) -> usize {
+        loop {
+            if let Some(&next) = self.nodes[state].next.get(&c) {
+                return next;
+            }
+            if state == ROOT {
+                return ROOT;
+            }
+            state = self.nodes[state].fail;
+        }
+    }
+
+    /// The ids of the patterns that end at the char that led to `state`.
+    pub fn matches(&self, state: usize) -> &[usize] {
+        &self.nodes[state].outputs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+
+    /// Every (end, pattern id) the automaton reports while reading `text`.
+    fn run(patterns: &[&str], text: &str) -> Vec<(usize, usize)> {
+        let patterns: Vec<Vec<char>> = patterns.iter().map(|p| p.chars().collect()).collect();
+        let automaton = AhoCorasick::new(&patterns);
+        let mut state = automaton.start();
+        let mut found = vec![];
+        for (end, c) in text.chars().enumerate() {
+            state = automaton.step(state, c);
+            found.extend(automaton.matches(state).iter().map(|&id| (end, id)));
+        }
+        found.sort();
+        found
+    }
+
+    fn brute_force(patterns: &[&str], text: &str) -> Vec<(usize, usize)> {
+        let mut found = vec![];
+        for (id, pattern) in patterns.iter().enumerate() {
+            for start in 0..text.len() {
+                if text[start..].starts_with(pattern) {
+                    found.push((start + pattern.len() - 1, id));
+                }
+            }
+        }
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn inherits_outputs_through_failure_links() {
+        assert_eq!(
+            run(&["ABC", "BC", "C"], "ABC"),
+            vec![(2, 0), (2, 1), (2, 2)]
+        );
+        // Reaching BCE means falling back out of the middle of ABCD.
+        assert_eq!(run(&["ABCD", "BCE"], "ABCE"), vec![(3, 1)]);
+    }
+
+    #[test]
+    fn finds_overlapping_patterns() {
+        assert_eq!(run(&["AB", "B"], "ABB"), vec![(1, 0), (1, 1), (2, 1)]);
+        assert_eq!(run(&["AA"], "AAAA"), vec![(1, 0), (2, 0), (3, 0)]);
+    }
+
+    #[test]
+    fn finds_a_word_and_its_reverse() {
+        assert_eq!(run(&["XMAS", "SAMX"], "XMASAMX"), vec![(3, 0), (6, 1)]);
+        // A palindrome is its own reverse, so both ids match together.
+        assert_eq!(
+            run(&["MAM", "MAM"], "MAMAM"),
+            vec![(2, 0), (2, 1), (4, 0), (4, 1)]
+        );
+    }
+
+    #[test]
+    fn agrees_with_brute_force() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let random = |rng: &mut StdRng, len: usize| -> String {
+            (0..len)
+                .map(|_| ['A', 'B', 'C'][rng.gen_range(0..3)])
+                .collect()
+        };
+        for _ in 0..500 {
+            let patterns: Vec<String> = (0..rng.gen_range(1..6))
+                .map(|_| {
+                    let len = rng.gen_range(1..5);
+                    random(&mut rng, len)
+                })
+                .collect();
+            let patterns: Vec<&str> = patterns.iter().map(String::as_str).collect();
+            let text = random(&mut rng, 40);
+            assert_eq!(
+                run(&patterns, &text),
+                brute_force(&patterns, &text),
+                "{patterns:?} in {text}"
+            );
+        }
+    }
+}
@@ -1,15 +1,25 @@
 use anyhow::anyhow;
 use anyhow::bail;
 use anyhow::Result;
+use grid::map::Map;
 use humantime::format_duration;
-use std::char;
-use std::str::Chars;
+use std::env;
 use std::{fs::read_to_string, time::Instant};
+use wordsearch::{find_words, Pattern};
+mod aho_corasick;
+mod grid;
+mod wordsearch;
 
-type ProcessedInput = Vec<Vec<char>>;
-type Output = u32;
+type ProcessedInput = Map<char>;
+type Output = usize;
+
+const X_MAS: &str = "
+M.S
+.A.
+M.S";
 
 fn main() -> Result<()> {
+    let args = Args::parse(env::args().skip(1))?;
     let input = read_to_string("input.txt")?;
     println!("Lines in Input: {}", input.len());
 
@@ -17,12 +27,12 @@ fn main() -> Result<()> {
     let processed = process_input(input)?;
     let time_processing = processing_start.elapsed();
 
-    let part_one = solve_part_one(processed.clone());
     let part_one_start = Instant::now();
+    let part_one = solve_part_one(processed.clone());
     let time_one = part_one_start.elapsed();
 
     let part_two_start = Instant::now();
-    let part_two = solve_part_two(processed);
+    let part_two = solve_part_two(processed.clone());
     let time_two = part_two_start.elapsed();
 
     println!(
@@ -39,111 +49,70 @@ fn main() -> Result<()> {
         part_two,
         format_duration(time_two).to_string()
     );
-    Ok(())
-}
 
-fn process_input(input: String) -> Result<ProcessedInput> {
-    Ok(input
-        .lines()
-        .filter(|x| !x.is_empty())
-        .map(|x| x.chars().collect::<Vec<char>>())
-        .collect())
-}
-
-fn solve_part_one(data: ProcessedInput) -> Result<Output> {
-    let mut count = 0;
-    let width = data.first().unwrap().len();
-    for i in 0..data.len() {
-        for j in 0..width {
-            count += matches_in_wordsearch(&data, i, j)?
+    if let Some(words) = args.words {
+        let words: Vec<&str> = words.split(',').collect();
+        let matches = find_words(&processed, &words);
+        for word in words {
+            let found: Vec<_> = matches.iter().filter(|m| m.word == word).collect();
+            println!("{word}: {} matches", found.len());
+            for m in found {
+                let cells: Vec<_> = m.cells.iter().map(|c| (c.row, c.col)).collect();
+                println!("  heading {:?}: {:?}", m.direction, cells);
+            }
         }
     }
-    Ok(count)
-}
-
-fn matches_in_wordsearch(data: &ProcessedInput, x: usize, y: usize) -> Result<u32> {
-    let string_to_find = "XMAS".chars();
-    let directions = [
-        (0, 1),
-        (1, 1),
-        (0, -1),
-        (-1, 1),
-        (1, -1),
-        (-1, -1),
-        (1, 0),
-        (-1, 0),
-    ];
-    let mut count = 0;
-    for (dx, dy) in directions {
-        if let Ok(true) = match_found_recursive(string_to_find.clone(), data, x, y, dx, dy) {
-            count += 1;
+    if let Some(path) = args.pattern_path {
+        let pattern = Pattern::parse(&read_to_string(&path)?)?;
+        for rotation in pattern.rotations() {
+            for origin in rotation.find(&processed) {
+                println!("Pattern at ({}, {})", origin.row, origin.col);
+            }
         }
     }
-    Ok(count)
+    Ok(())
 }
 
-fn match_found_recursive(
-    mut string_to_find: Chars,
-    data: &ProcessedInput,
-    x: usize,
-    y: usize,
-    dx: i32,
-    dy: i32,
-) -> Result<bool> {
-    let desired_char = string_to_find.next();
-    let desired_char = match desired_char {
-        Some(t) => t,
-        None => return Ok(true),
-    };
-    let char = data
-        .get(x)
-        .ok_or(anyhow!("Exceeds height"))?
-        .get(y)
-        .ok_or(anyhow!("Exceeds width"))?;
-    let next_x = x as i32 + dx;
-    let next_y = y as i32 + dy;
-
-    if desired_char == *char {
-        return match_found_recursive(
-            string_to_find,
-            data,
-            next_x as usize,
-            next_y as usize,
-            dx,
-            dy,
-        );
-    }
-    Ok(false)
+/// Usage: day04 [--words WORD,WORD,...] [--pattern PATH]
+/// Patterns are small grids of characters where '.' matches anything.
+struct Args {
+    words: Option<String>,
+    pattern_path: Option<String>,
 }
 
-fn solve_part_two(data: ProcessedInput) -> Result<Output> {
-    let mut count = 0;
-    let width = data.first().unwrap().len();
-    for i in 0..data.len() {
-        for j in 0..width {
-            if let Ok(true) = mas_match(&data, i, j) {
-                count += 1
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Args {
+            words: None,
+            pattern_path: None,
+        };
+        while let Some(flag) = args.next() {
+            let value = args
+                .next()
+                .ok_or(anyhow!("Missing value for argument {flag}"))?;
+            match flag.as_str() {
+                "--words" => parsed.words = Some(value),
+                "--pattern" => parsed.pattern_path = Some(value),
+                _ => bail!("Unknown argument {flag}"),
             }
         }
+        Ok(parsed)
     }
-    Ok(count)
 }
 
-fn mas_match(data: &ProcessedInput, x: usize, y: usize) -> Result<bool> {
-    let diag_one = check_diagonal(data, x, y, (1, 1))?;
-    let diag_two = check_diagonal(data, x, y, (-1, 1))?;
-    Ok(diag_one && diag_two)
+fn process_input(input: String) -> Result<ProcessedInput> {
+    Map::from_str(&input, Ok)
 }
 
-fn check_diagonal(data: &ProcessedInput, x: usize, y: usize, dir: (i32, i32)) -> Result<bool> {
-    let opp = (-dir.0, -dir.1);
-    let rhs_center = "AS".chars();
-    let lhs_center = "AM".chars();
-    let normal_rhs = match_found_recursive(rhs_center.clone(), data, x, y, dir.0, dir.1)?;
-    let oppposite_rhs = match_found_recursive(rhs_center, data, x, y, opp.0, opp.1)?;
-    let normal_lhs = match_found_recursive(lhs_center.clone(), data, x, y, dir.0, dir.1)?;
-    let oppposite_lhs = match_found_recursive(lhs_center, data, x, y, opp.0, opp.1)?;
-    let diag_one = normal_lhs && oppposite_rhs;
-    let diag_two = normal_rhs && oppposite_lhs;
-    Ok(diag_one || diag_two)
+fn solve_part_one(data: ProcessedInput) -> Result<Output> {
+    Ok(find_words(&data, &["XMAS"]).len())
+}
+
+fn solve_part_two(data: ProcessedInput) -> Result<Output> {
+    let cross = Pattern::parse(X_MAS)?;
+    Ok(cross
+        .rotations()
+        .iter()
+        .map(|pattern| pattern.find(&data).len())
+        .sum())
 }
@@ -0,0 +1,285 @@
+use anyhow::Result;
+
+use crate::{
+    aho_corasick::AhoCorasick,
+    grid::{coordinate::Coordinate, direction::Direction, map::Map},
+};
+
+/// Matches any character in a pattern.
+pub const WILDCARD: char = '.';
+
+pub struct WordMatch<'a> {
+    pub word: &'a str,
+    pub direction: Direction,
+    pub cells: Vec<Coordinate>,
+}
+
+/// Finds every occurrence of every word, reading in all 8 directions.
+/// The words and their reverses go into one Aho-Corasick automaton, and each
+/// row, column and diagonal is streamed through it once. A match of a
+/// reversed word is a match of the word read the other way along the line.
+pub fn find_words<'a>(map: &Map<char>, words: &[&'a str]) -> Vec<WordMatch<'a>> {
+    let words: Vec<&str> = words.iter().copied().filter(|w| !w.is_empty()).collect();
+    let patterns: Vec<Vec<char>> = words
+        .iter()
+        .flat_map(|w| [w.chars().collect(), w.chars().rev().collect()])
+        .collect();
+    let lengths: Vec<usize> = patterns.iter().map(|p| p.len()).collect();
+    let automaton = AhoCorasick::new(&patterns);
+
+    let mut matches = vec![];
+    for (direction, line) in lines(map) {
+        let mut state = automaton.start();
+        for (end, pos) in line.iter().enumerate() {
+            state = automaton.step(state, *map.get(pos).unwrap());
+            for &id in automaton.matches(state) {
+                let mut cells = line[end + 1 - lengths[id]..=end].to_vec();
+                let mut direction = direction.clone();
+                if id % 2 == 1 {
+                    cells.reverse();
+                    direction = direction.opposite();
+                }
+                matches.push(WordMatch {
+                    word: words[id / 2],
+                    direction,
+                    cells,
+                });
+            }
+        }
+    }
+    matches
+}
+
+/// Every row, column and diagonal of the map, each paired with the
+/// direction it is read in.
+fn lines(map: &Map<char>) -> Vec<(Direction, Vec<Coordinate>)> {
+    let mut lines = vec![];
+    let forwards = [
+        Direction::East,
+        Direction::South,
+        Direction::SouthEast,
+        Direction::SouthWest,
+    ];
+    for direction in forwards {
+        let backwards = direction.opposite();
+        for (start, _) in map {
+            if map.get_relative_cell(&start, backwards.clone()).is_some() {
+                continue;
+            }
+            let mut line = vec![start];
+            let mut pos = start;
+            while let Some((next, _)) = map.get_relative_cell(&pos, direction.clone()) {
+                line.push(next);
+                pos = next;
+            }
+            lines.push((direction.clone(), line));
+        }
+    }
+    lines
+}
+
+/// A small grid of characters to stamp over the word search, such as the
+/// X-MAS cross. Cells holding the wildcard match anything.
+#[derive(Clone)]
+pub struct Pattern {
+    cells: Map<Option<char>>,
+}
+
+impl Pattern {
+    pub fn parse(input: &str) -> Result<Self> {
+        let cells = Map::from_str(input.trim(), |c| {
+            Ok(if c == WILDCARD { None } else { Some(c) })
+        })?;
+        Ok(Self { cells })
+    }
+
+    /// The pattern turned through each quarter turn, without duplicates.
+    pub fn rotations(&self) -> Vec<Pattern> {
+        let mut rotations: Vec<Pattern> = vec![];
+        let mut current = self.clone();
+        for _ in 0..4 {
+            if !rotations
+                .iter()
+                .any(|r| r.cells.cells == current.cells.cells)
+            {
+                rotations.push(current.clone());
+            }
+            current.cells.cells.rotate_right();
+        }
+        rotations
+    }
+
+    /// Returns the top left corner of every place the pattern fits.
+    pub fn find(&self, map: &Map<char>) -> Vec<Coordinate> {
+        let mut origins = vec![];
+        for (origin, _) in map {
+            if self.matches_at(map, origin) {
+                origins.push(origin);
+            }
+        }
+        origins
+    }
+
+    fn matches_at(&self, map: &Map<char>, origin: Coordinate) -> bool {
+        self.cells.into_iter().all(|(offset, expected)| {
+            let Some(pos) = origin.checked_add(offset.row, offset.col) else {
+                return false;
+            };
+            match (map.get(&pos), expected) {
+                (None, _) => false,
+                (Some(_), None) => true,
+                (Some(actual), Some(expected)) => actual == expected,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    use super::*;
+    use crate::{process_input, solve_part_one, solve_part_two};
+
+    const DIRECTIONS: [Direction; 8] = [
+        Direction::North,
+        Direction::South,
+        Direction::East,
+        Direction::West,
+        Direction::NorthEast,
+        Direction::NorthWest,
+        Direction::SouthEast,
+        Direction::SouthWest,
+    ];
+
+    const EXAMPLE: &str = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX
+";
+
+    fn map(input: &str) -> Map<char> {
+        process_input(input.to_string()).unwrap()
+    }
+
+    #[test]
+    fn solves_the_example() {
+        assert_eq!(solve_part_one(map(EXAMPLE)).unwrap(), 18);
+        assert_eq!(solve_part_two(map(EXAMPLE)).unwrap(), 9);
+    }
+
+    #[test]
+    fn reads_words_in_every_direction() {
+        for direction in DIRECTIONS {
+            let mut cells = vec![vec!['.'; 5]; 5];
+            let (delta_row, delta_col) = direction.to_delta();
+            let expected: Vec<Coordinate> = (0..3)
+                .map(|i| {
+                    Coordinate::new((2 + delta_row * i) as usize, (2 + delta_col * i) as usize)
+                })
+                .collect();
+            for (pos, c) in expected.iter().zip("CAT".chars()) {
+                cells[pos.row][pos.col] = c;
+            }
+            let input: Vec<String> = cells.iter().map(|row| row.iter().collect()).collect();
+
+            let matches = find_words(&map(&input.join("\n")), &["CAT"]);
+            assert_eq!(matches.len(), 1, "reading {direction:?}");
+            assert_eq!(matches[0].word, "CAT");
+            assert_eq!(matches[0].direction, direction);
+            assert_eq!(matches[0].cells, expected);
+        }
+    }
+
+    #[test]
+    fn wildcards_match_anything() {
+        let pattern = Pattern::parse("A.\n.B").unwrap();
+        let origins = pattern.find(&map("AXA\nABY\nABY"));
+        assert_eq!(origins, vec![Coordinate::new(0, 0), Coordinate::new(1, 0)]);
+        // The pattern has to fit inside the map.
+        assert!(pattern.find(&map("AB")).is_empty());
+    }
+
+    #[test]
+    fn drops_repeated_rotations() {
+        let count = |input: &str| Pattern::parse(input).unwrap().rotations().len();
+        assert_eq!(count(crate::X_MAS), 4);
+        assert_eq!(count("M.M\n.A.\nM.M"), 1);
+        assert_eq!(count("MAM"), 2);
+        assert_eq!(count("MAS"), 4);
+    }
+
+    /// Every match as (word, direction, cells), in a fixed order.
+    fn summarise(matches: Vec<WordMatch>) -> Vec<(String, String, Vec<Coordinate>)> {
+        let mut summary: Vec<_> = matches
+            .into_iter()
+            .map(|m| (m.word.to_string(), format!("{:?}", m.direction), m.cells))
+            .collect();
+        summary.sort_by_key(|(word, direction, cells)| {
+            (
+                word.clone(),
+                direction.clone(),
+                cells.iter().map(|c| (c.row, c.col)).collect::<Vec<_>>(),
+            )
+        });
+        summary
+    }
+
+    /// Tries every word from every cell in every direction.
+    fn naive_find<'a>(map: &Map<char>, words: &[&'a str]) -> Vec<WordMatch<'a>> {
+        let mut matches = vec![];
+        for word in words {
+            for (start, _) in map {
+                for direction in DIRECTIONS {
+                    let mut cells = vec![start];
+                    for _ in 1..word.chars().count() {
+                        let last = *cells.last().unwrap();
+                        match map.get_relative_cell(&last, direction.clone()) {
+                            Some((next, _)) => cells.push(next),
+                            None => break,
+                        }
+                    }
+                    let read: String = cells.iter().map(|c| map.get(c).unwrap()).collect();
+                    if read == *word {
+                        matches.push(WordMatch {
+                            word,
+                            direction,
+                            cells,
+                        });
+                    }
+                }
+            }
+        }
+        matches
+    }
+
+    #[test]
+    fn agrees_with_a_naive_search() {
+        let mut rng = StdRng::seed_from_u64(4);
+        // Palindromes and single letters match a word and its reverse in
+        // the same place, and "AS" overlaps the ends of the longer words.
+        let words = ["XMAS", "MAM", "AS", "S", "SAMXS"];
+        for _ in 0..200 {
+            let (rows, cols) = (rng.gen_range(1..12), rng.gen_range(1..12));
+            let input: Vec<String> = (0..rows)
+                .map(|_| {
+                    (0..cols)
+                        .map(|_| ['X', 'M', 'A', 'S'][rng.gen_range(0..4)])
+                        .collect()
+                })
+                .collect();
+            let map = map(&input.join("\n"));
+            assert_eq!(
+                summarise(find_words(&map, &words)),
+                summarise(naive_find(&map, &words)),
+                "in\n{map}"
+            );
+        }
+    }
+}
@@ -0,0 +1,269 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+};
+
+use crate::{graph::RuleGraph, Page};
+
+/// A rule `before|after` broken by an update, with the 0-based positions the
+/// two pages were found at.
+pub struct Violation {
+    pub before: Page,
+    pub after: Page,
+    pub before_position: usize,
+    pub after_position: usize,
+}
+
+/// Everything wrong with a single update.
+pub struct Diagnosis<'a> {
+    book: &'a [Page],
+    violations: Vec<Violation>,
+    /// None when the rules contain a cycle, so nothing would fix it.
+    swaps: Option<Swaps>,
+}
+
+/// How many swaps of two pages it takes to satisfy every rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Swaps {
+    Minimum(usize),
+    /// Too many orders satisfy the rules to check them all, so this is the
+    /// best found among the ones that were.
+    AtMost(usize),
+}
+
+/// How many partial orders to try before settling for the best so far.
+const STEP_LIMIT: usize = 20_000;
+
+impl<'a> Diagnosis<'a> {
+    pub fn new(book: &'a [Page], rule_graph: &RuleGraph) -> Self {
+        let mut violations = vec![];
+        for (after_position, &after) in book.iter().enumerate() {
+            for (offset, &before) in book[after_position + 1..].iter().enumerate() {
+                if rule_graph.must_precede(before, after) {
+                    violations.push(Violation {
+                        before,
+                        after,
+                        before_position: after_position + 1 + offset,
+                        after_position,
+                    });
+                }
+            }
+        }
+        let swaps = rule_graph
+            .sort(book)
+            .ok()
+            .map(|_| fewest_swaps(book, rule_graph));
+        Self {
+            book,
+            violations,
+            swaps,
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+}
+
+/// Searches the orders of the pages that satisfy the rules for the one
+/// closest to the update. The rules usually order an update completely, so
+/// there's normally only one to find.
+fn fewest_swaps(book: &[Page], rule_graph: &RuleGraph) -> Swaps {
+    let mut distinct = vec![];
+    for &page in book {
+        if !distinct.contains(&page) {
+            distinct.push(page);
+        }
+    }
+    let mut search = OrderSearch {
+        book,
+        rule_graph,
+        distinct: &distinct,
+        placed: HashSet::new(),
+        target: vec![],
+        misplaced: 0,
+        best: usize::MAX,
+        steps: 0,
+    };
+    search.extend();
+    match search.steps > STEP_LIMIT {
+        true => Swaps::AtMost(search.best),
+        false => Swaps::Minimum(search.best),
+    }
+}
+
+struct OrderSearch<'a> {
+    book: &'a [Page],
+    rule_graph: &'a RuleGraph,
+    distinct: &'a [Page],
+    placed: HashSet<Page>,
+    /// The order so far, with every copy of each placed page.
+    target: Vec<Page>,
+    /// How many pages of `target` differ from the book at the same position.
+    misplaced: usize,
+    best: usize,
+    steps: usize,
+}
+
+impl OrderSearch<'_> {
+    /// Places each page whose required predecessors are all placed already,
+    /// trying the page the book has next first. A swap fixes at most two
+    /// misplaced pages, so branches that can't beat the best are dropped.
+    fn extend(&mut self) {
+        self.steps += 1;
+        if self.steps > STEP_LIMIT || self.misplaced.div_ceil(2) >= self.best {
+            return;
+        }
+        if self.target.len() == self.book.len() {
+            self.best = swaps_between(self.book, &self.target);
+            return;
+        }
+        let next = self.book[self.target.len()];
+        let candidates = std::iter::once(next).chain(self.distinct.iter().copied());
+        for (i, page) in candidates.enumerate() {
+            if (i > 0 && page == next) || !self.is_ready(page) {
+                continue;
+            }
+            let (length, misplaced) = (self.target.len(), self.misplaced);
+            for _ in self.book.iter().filter(|&&p| p == page) {
+                if self.book[self.target.len()] != page {
+                    self.misplaced += 1;
+                }
+                self.target.push(page);
+            }
+            self.placed.insert(page);
+            self.extend();
+            self.placed.remove(&page);
+            self.target.truncate(length);
+            self.misplaced = misplaced;
+        }
+    }
+
+    fn is_ready(&self, page: Page) -> bool {
+        !self.placed.contains(&page)
+            && self.distinct.iter().all(|&before| {
+                self.placed.contains(&before) || !self.rule_graph.must_precede(before, page)
+            })
+    }
+}
+
+/// The fewest swaps that turn `from` into `to`: each cycle of the
+/// permutation between them of length k takes k - 1 swaps. Repeated pages
+/// are matched up in the order they appear.
+fn swaps_between(from: &[Page], to: &[Page]) -> usize {
+    let mut target_positions: HashMap<Page, Vec<usize>> = HashMap::new();
+    for (i, &page) in to.iter().enumerate().rev() {
+        target_positions.entry(page).or_default().push(i);
+    }
+    let target: Vec<usize> = from
+        .iter()
+        .map(|page| target_positions.get_mut(page).unwrap().pop().unwrap())
+        .collect();
+    let mut visited = vec![false; from.len()];
+    let mut swaps = 0;
+    for start in 0..from.len() {
+        let mut cycle_length: usize = 0;
+        let mut i = start;
+        while !visited[i] {
+            visited[i] = true;
+            i = target[i];
+            cycle_length += 1;
+        }
+        swaps += cycle_length.saturating_sub(1);
+    }
+    swaps
+}
+
+impl Display for Diagnosis<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let pages: Vec<_> = self.book.iter().map(|p| p.to_string()).collect();
+        writeln!(f, "{}", pages.join(","))?;
+        for v in &self.violations {
+            writeln!(
+                f,
+                "  breaks {}|{}: {} at position {} comes after {} at position {}",
+                v.before, v.after, v.before, v.before_position, v.after, v.after_position
+            )?;
+        }
+        match self.swaps {
+            Some(Swaps::Minimum(swaps)) => write!(f, "  minimum swaps to fix: {swaps}"),
+            Some(Swaps::AtMost(swaps)) => write!(
+                f,
+                "  swaps to fix: at most {swaps}, too many valid orders to find the minimum"
+            ),
+            None => write!(f, "  cannot be fixed, its rules contain a cycle"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(rules: &[(Page, Page)]) -> RuleGraph {
+        let mut graph = RuleGraph::default();
+        for &(before, after) in rules {
+            graph.add_rule(before, after);
+        }
+        graph
+    }
+
+    #[test]
+    fn finds_the_minimum_for_a_partial_order() {
+        // Kahn's algorithm would give 2,3,1, two swaps away, but swapping
+        // 1 and 3 alone gives 3,2,1 which is just as valid.
+        let rules = graph(&[(3, 1)]);
+        let diagnosis = Diagnosis::new(&[1, 2, 3], &rules);
+        assert_eq!(diagnosis.swaps, Some(Swaps::Minimum(1)));
+        assert_eq!(
+            diagnosis.to_string(),
+            "1,2,3\n  breaks 3|1: 3 at position 2 comes after 1 at position 0\n  minimum swaps to fix: 1"
+        );
+    }
+
+    #[test]
+    fn counts_swaps_for_total_orders() {
+        let rules = graph(&[(97, 75), (97, 47), (75, 47), (97, 13), (75, 13), (47, 13)]);
+        assert_eq!(
+            Diagnosis::new(&[13, 47, 75, 97], &rules).swaps,
+            Some(Swaps::Minimum(2))
+        );
+        assert_eq!(
+            Diagnosis::new(&[75, 97, 47, 13], &rules).swaps,
+            Some(Swaps::Minimum(1))
+        );
+        assert!(Diagnosis::new(&[97, 75, 47, 13], &rules).is_valid());
+    }
+
+    #[test]
+    fn handles_duplicates_and_cycles() {
+        let rules = graph(&[(1, 2)]);
+        assert_eq!(
+            Diagnosis::new(&[2, 1, 2], &rules).swaps,
+            Some(Swaps::Minimum(1))
+        );
+        let cyclic = graph(&[(1, 2), (2, 1)]);
+        let diagnosis = Diagnosis::new(&[1, 2], &cyclic);
+        assert_eq!(diagnosis.swaps, None);
+        assert!(diagnosis
+            .to_string()
+            .ends_with("cannot be fixed, its rules contain a cycle"));
+    }
+
+    #[test]
+    fn settles_for_an_upper_bound() {
+        // Rules that only say the odd pages come before the even ones leave
+        // far too many valid orders to search. Six swaps would do.
+        let odd = (1..=23).step_by(2);
+        let rules = graph(
+            &odd.flat_map(|o| (2..=22).step_by(2).map(move |e| (o, e)))
+                .collect::<Vec<_>>(),
+        );
+        let book: Vec<Page> = (1..=23).collect();
+        let swaps = Diagnosis::new(&book, &rules).swaps;
+        assert!(
+            matches!(swaps, Some(Swaps::AtMost(n)) if n >= 6),
+            "{swaps:?}"
+        );
+    }
+}
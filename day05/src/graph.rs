@@ -0,0 +1,226 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    error::Error,
+    fmt::Display,
+};
+
+use crate::Page;
+
+/// The page ordering rules as a dependency graph.
+/// An edge `X -> Y` means page X must be printed before page Y.
+#[derive(Clone, Debug, Default)]
+pub struct RuleGraph {
+    edges: HashMap<Page, HashSet<Page>>,
+}
+
+#[derive(Debug)]
+pub enum OrderingError {
+    /// The rules restricted to an update's pages contain a cycle, so no
+    /// ordering can satisfy them. The pages are listed in cycle order.
+    Cycle { pages: Vec<Page> },
+}
+
+impl Display for OrderingError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrderingError::Cycle { pages } => {
+                let cycle: Vec<_> = pages.iter().map(|p| p.to_string()).collect();
+                write!(f, "Ordering rules contain a cycle: {}", cycle.join(" -> "))?;
+                if let Some(first) = pages.first() {
+                    write!(f, " -> {first}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for OrderingError {}
+
+impl RuleGraph {
+    pub fn add_rule(&mut self, before: Page, after: Page) {
+        self.edges.entry(before).or_default().insert(after);
+    }
+
+    pub fn must_precede(&self, before: Page, after: Page) -> bool {
+        self.edges.get(&before).is_some_and(|e| e.contains(&after))
+    }
+
+    /// Whether no rule requires a later page to come before an earlier one.
+    pub fn is_ordered(&self, book: &[Page]) -> bool {
+        for (i, &page) in book.iter().enumerate() {
+            if book[i + 1..]
+                .iter()
+                .any(|&later| self.must_precede(later, page))
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Orders the pages with Kahn's algorithm over the subgraph induced by
+    /// the pages. Pages that are unconstrained keep their relative order, and
+    /// a page listed more than once has its copies kept together.
+    pub fn sort(&self, book: &[Page]) -> Result<Vec<Page>, OrderingError> {
+        let mut first_position: HashMap<Page, usize> = HashMap::new();
+        let mut copies: HashMap<Page, usize> = HashMap::new();
+        for (position, &page) in book.iter().enumerate() {
+            first_position.entry(page).or_insert(position);
+            *copies.entry(page).or_default() += 1;
+        }
+        let pages: HashSet<Page> = first_position.keys().copied().collect();
+        let mut distinct: Vec<Page> = pages.iter().copied().collect();
+        distinct.sort_by_key(|p| first_position[p]);
+
+        let mut in_degree: HashMap<Page, usize> = distinct.iter().map(|&p| (p, 0)).collect();
+        for &page in &distinct {
+            for after in self.successors_within(page, &pages) {
+                *in_degree.get_mut(&after).unwrap() += 1;
+            }
+        }
+
+        let mut ready: VecDeque<Page> = distinct
+            .iter()
+            .copied()
+            .filter(|p| in_degree[p] == 0)
+            .collect();
+        let mut sorted = Vec::with_capacity(distinct.len());
+        while let Some(page) = ready.pop_front() {
+            sorted.push(page);
+            let mut freed = vec![];
+            for after in self.successors_within(page, &pages) {
+                let degree = in_degree.get_mut(&after).unwrap();
+                *degree -= 1;
+                if *degree == 0 {
+                    freed.push(after);
+                }
+            }
+            freed.sort_by_key(|p| first_position[p]);
+            ready.extend(freed);
+        }
+
+        if sorted.len() < distinct.len() {
+            let remaining: HashSet<Page> = in_degree
+                .into_iter()
+                .filter(|(_, degree)| *degree > 0)
+                .map(|(page, _)| page)
+                .collect();
+            return Err(OrderingError::Cycle {
+                pages: self.find_cycle(book, &remaining),
+            });
+        }
+        Ok(sorted
+            .into_iter()
+            .flat_map(|page| std::iter::repeat_n(page, copies[&page]))
+            .collect())
+    }
+
+    /// Renders every rule as a Graphviz digraph, sorted so the output is stable.
+    pub fn to_dot(&self) -> String {
+        let mut rules: Vec<(Page, Page)> = self
+            .edges
+            .iter()
+            .flat_map(|(&before, afters)| afters.iter().map(move |&after| (before, after)))
+            .collect();
+        rules.sort();
+        let mut dot = String::from("digraph rules {\n");
+        for (before, after) in rules {
+            dot.push_str(&format!("    {before} -> {after};\n"));
+        }
+        dot.push('}');
+        dot.push('\n');
+        dot
+    }
+
+    fn successors_within<'a>(
+        &'a self,
+        page: Page,
+        pages: &'a HashSet<Page>,
+    ) -> impl Iterator<Item = Page> + 'a {
+        self.edges
+            .get(&page)
+            .into_iter()
+            .flatten()
+            .copied()
+            .filter(|p| pages.contains(p))
+    }
+
+    /// Every page Kahn's algorithm couldn't place still has a predecessor
+    /// among the remaining pages, so walking predecessors must revisit a page.
+    fn find_cycle(&self, book: &[Page], remaining: &HashSet<Page>) -> Vec<Page> {
+        let start = *book.iter().find(|p| remaining.contains(p)).unwrap();
+        let mut path = vec![start];
+        let mut current = start;
+        loop {
+            current = *book
+                .iter()
+                .find(|&&p| remaining.contains(&p) && self.must_precede(p, current))
+                .unwrap();
+            if let Some(position) = path.iter().position(|&p| p == current) {
+                let mut cycle = path.split_off(position);
+                cycle.reverse();
+                return cycle;
+            }
+            path.push(current);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(rules: &[(Page, Page)]) -> RuleGraph {
+        let mut graph = RuleGraph::default();
+        for &(before, after) in rules {
+            graph.add_rule(before, after);
+        }
+        graph
+    }
+
+    #[test]
+    fn sorts_with_kahns_algorithm() {
+        let example = "47|53\n97|13\n97|61\n97|47\n75|29\n61|13\n75|53\n29|13\n97|29\n53|29\n61|53\n97|53\n61|29\n47|13\n75|47\n97|75\n47|61\n75|61\n47|29\n75|13\n53|13\n\n";
+        let rules = crate::generate_ordering_rules(&mut example.lines()).unwrap();
+        assert_eq!(
+            rules.sort(&[75, 97, 47, 61, 53]).unwrap(),
+            vec![97, 75, 47, 61, 53]
+        );
+        assert_eq!(rules.sort(&[61, 13, 29]).unwrap(), vec![61, 29, 13]);
+        assert_eq!(
+            rules.sort(&[97, 13, 75, 29, 47]).unwrap(),
+            vec![97, 75, 47, 29, 13]
+        );
+        // Unconstrained pages keep their order.
+        assert_eq!(
+            rules.sort(&[5, 13, 3, 61, 4]).unwrap(),
+            vec![5, 3, 61, 4, 13]
+        );
+    }
+
+    #[test]
+    fn keeps_duplicate_pages_together() {
+        let rules = graph(&[(1, 2)]);
+        assert_eq!(rules.sort(&[2, 1, 2]).unwrap(), vec![1, 2, 2]);
+        assert_eq!(rules.sort(&[2, 2, 1, 1]).unwrap(), vec![1, 1, 2, 2]);
+        assert_eq!(rules.sort(&[3, 3]).unwrap(), vec![3, 3]);
+    }
+
+    #[test]
+    fn reports_cycles() {
+        let rules = graph(&[(1, 2), (2, 3), (3, 1), (4, 1)]);
+        let Err(OrderingError::Cycle { pages }) = rules.sort(&[4, 1, 2, 3]) else {
+            panic!("expected a cycle");
+        };
+        assert_eq!(pages, vec![2, 3, 1]);
+        assert_eq!(
+            OrderingError::Cycle { pages }.to_string(),
+            "Ordering rules contain a cycle: 2 -> 3 -> 1 -> 2"
+        );
+        // The same rules are fine for an update that skips part of the cycle.
+        assert_eq!(rules.sort(&[3, 4, 1]).unwrap(), vec![3, 4, 1]);
+        let duplicated = rules.sort(&[1, 2, 3, 1]);
+        assert!(matches!(duplicated, Err(OrderingError::Cycle { .. })));
+    }
+}
@@ -1,12 +1,21 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
+use diagnostics::Diagnosis;
+use graph::RuleGraph;
 use humantime::format_duration;
-use std::{collections::HashMap, fs::read_to_string, time::Instant};
+use std::{
+    env,
+    fs::{read_to_string, write},
+    time::Instant,
+};
+mod diagnostics;
+mod graph;
 
 type Page = i32;
-type ProcessedInput = (HashMap<Page, Vec<Page>>, Vec<Vec<Page>>);
+type ProcessedInput = (RuleGraph, Vec<Vec<Page>>);
 type Output = Page;
 
 fn main() -> Result<()> {
+    let args = Args::parse(env::args().skip(1))?;
     let input = read_to_string("input.txt")?;
     println!("Lines in Input: {}", input.lines().count());
 
@@ -19,7 +28,7 @@ fn main() -> Result<()> {
     let time_one = part_one_start.elapsed();
 
     let part_two_start = Instant::now();
-    let part_two = solve_part_two(processed);
+    let part_two = solve_part_two(processed.clone());
     let time_two = part_two_start.elapsed();
 
     println!(
@@ -36,9 +45,50 @@ fn main() -> Result<()> {
         part_two,
         format_duration(time_two).to_string()
     );
+
+    let (rule_graph, books) = processed;
+    if args.diagnose {
+        books
+            .iter()
+            .map(|book| Diagnosis::new(book, &rule_graph))
+            .filter(|diagnosis| !diagnosis.is_valid())
+            .for_each(|diagnosis| println!("{diagnosis}"));
+    }
+    if let Some(path) = args.dot_path {
+        write(&path, rule_graph.to_dot()).with_context(|| format!("Failed to write {path}"))?;
+        println!("Rule graph written to {path}");
+    }
     Ok(())
 }
 
+/// Usage: day05 [--diagnose] [--dot PATH]
+struct Args {
+    diagnose: bool,
+    dot_path: Option<String>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Args {
+            diagnose: false,
+            dot_path: None,
+        };
+        while let Some(flag) = args.next() {
+            match flag.as_str() {
+                "--diagnose" => parsed.diagnose = true,
+                "--dot" => {
+                    let path = args
+                        .next()
+                        .ok_or(anyhow!("Missing value for argument {flag}"))?;
+                    parsed.dot_path = Some(path);
+                }
+                _ => bail!("Unknown argument {flag}"),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
 fn process_input(input: String) -> Result<ProcessedInput> {
     let mut iter = input.lines();
     let ordering_rules = generate_ordering_rules(&mut iter)?;
@@ -62,29 +112,29 @@ fn generate_books(iter: &mut std::str::Lines<'_>) -> Result<Vec<Vec<Page>>> {
     Ok(books)
 }
 
-fn generate_ordering_rules(iter: &mut std::str::Lines<'_>) -> Result<HashMap<Page, Vec<Page>>> {
-    let mut rule_map = HashMap::new();
+fn generate_ordering_rules(iter: &mut std::str::Lines<'_>) -> Result<RuleGraph> {
+    let mut rule_graph = RuleGraph::default();
     loop {
         let rule_str = iter
             .next()
             .with_context(|| "Input should have a newline seperating rules from books")?;
         if rule_str.trim().is_empty() {
-            return Ok(rule_map);
+            return Ok(rule_graph);
         }
         let (left, right) = rule_str
             .split_once("|")
             .with_context(|| "Rules should be split by | delimiter")?;
         let left = left.parse()?;
         let right = right.parse()?;
-        rule_map.entry(left).or_insert(Vec::new()).push(right);
+        rule_graph.add_rule(left, right);
     }
 }
 
 fn solve_part_one(data: ProcessedInput) -> Result<Output> {
-    let (rule_map, books) = data;
+    let (rule_graph, books) = data;
     let sum = books
         .iter()
-        .filter(|book| is_valid_book(book.to_vec(), &rule_map).is_ok_and(|v| v))
+        .filter(|book| rule_graph.is_ordered(book))
         .map(|valid_book| get_middle_page(valid_book))
         .sum();
     Ok(sum)
@@ -95,81 +145,19 @@ fn get_middle_page(valid_book: &[Page]) -> Page {
     valid_book[middle]
 }
 
-fn is_valid_book(book: Vec<Page>, rule_map: &HashMap<Page, Vec<Page>>) -> Result<bool> {
-    for i in 0..book.len() {
-        let before = &book[0..i];
-        if !are_valid_predecessors(book[i], before, rule_map)? {
-            return Ok(false);
-        }
-    }
-    Ok(true)
-}
-
-fn are_valid_predecessors(
-    i: Page,
-    before: &[Page],
-    rule_map: &HashMap<Page, Vec<Page>>,
-) -> Result<bool> {
-    let blacklist = get_afterpages(i, rule_map);
-    for num in blacklist {
-        if before.contains(&num) {
-            return Ok(false);
-        }
-    }
-    Ok(true)
-}
-
 fn solve_part_two(data: ProcessedInput) -> Result<Output> {
-    let (rule_map, books) = data.clone();
+    let (rule_graph, books) = data;
     let invalid_book_sum = books
         .iter()
-        .filter(|book| !is_valid_book(book.to_vec(), &rule_map).is_ok_and(|v| v))
-        .map(|invalid_book| find_valid_book_ordering(invalid_book, &rule_map))
+        .filter(|book| !rule_graph.is_ordered(book))
+        .map(|invalid_book| {
+            rule_graph
+                .sort(invalid_book)
+                .with_context(|| format!("Failed to order update {invalid_book:?}"))
+        })
         .collect::<Result<Vec<Vec<Page>>>>()?
         .iter()
         .map(|valid_book| get_middle_page(valid_book))
         .sum();
     Ok(invalid_book_sum)
 }
-
-fn find_valid_book_ordering(
-    invalid_book: &[Page],
-    rule_map: &HashMap<Page, Vec<Page>>,
-) -> Result<Vec<Page>> {
-    let mut remaining_pages = invalid_book.to_vec();
-    let mut valid_book = Vec::new();
-    while !remaining_pages.is_empty() {
-        for i in 0..remaining_pages.len() {
-            let attempt = remaining_pages[i];
-            let mut others = remaining_pages[0..i].to_vec();
-            others.append(&mut remaining_pages[i + 1..].to_vec());
-            if predecessor_count(attempt, others, rule_map)? == 0 {
-                remaining_pages.remove(i);
-                valid_book.push(attempt);
-                break;
-            }
-        }
-    }
-    valid_book.reverse();
-    if !is_valid_book(valid_book.clone(), rule_map)? {
-        panic!(
-            "Book was made valid, but isn't: {:#?} -> {:#?}",
-            invalid_book, valid_book
-        );
-    }
-    Ok(valid_book)
-}
-
-fn predecessor_count(
-    attempt: Page,
-    others: Vec<Page>,
-    rule_map: &HashMap<Page, Vec<Page>>,
-) -> Result<usize> {
-    let blacklist = get_afterpages(attempt, rule_map);
-    let count = others.iter().filter(|p| blacklist.contains(p)).count();
-    Ok(count)
-}
-
-fn get_afterpages(page: Page, rule_map: &HashMap<Page, Vec<Page>>) -> Vec<Page> {
-    rule_map.get(&page).map(|v| v.to_vec()).unwrap_or_default()
-}
@@ -2,11 +2,17 @@ use anyhow::anyhow;
 use anyhow::bail;
 use anyhow::Result;
 use humantime::format_duration;
+use memory::{report, Phase};
 use ndarray::Array2;
 use rayon::iter::IntoParallelRefIterator;
 use rayon::iter::ParallelIterator;
 use std::collections::HashSet;
 use std::{fs::read_to_string, time::Instant};
+mod memory;
+
+#[cfg(feature = "memory")]
+#[global_allocator]
+static ALLOCATOR: memory::Counting = memory::Counting;
 
 type ProcessedInput = Map;
 type Output = u32;
@@ -55,35 +61,44 @@ impl Facing {
 }
 
 fn main() -> Result<()> {
-    let input = read_to_string("/home/jayden/Documents/Code/AdventOfCode/2024/day06/input.txt")?;
+    let input = read_to_string("input.txt")?;
     println!("Lines in Input: {}", input.lines().count());
 
+    let memory_processing = Phase::start();
     let processing_start = Instant::now();
     let processed = process_input(input)?;
     let time_processing = processing_start.elapsed();
+    let usage_processing = memory_processing.finish();
     println!(
         "Processing Complete. (Took {:?})",
         format_duration(time_processing).to_string()
     );
+    report(usage_processing);
 
+    let memory_one = Phase::start();
     let part_one_start = Instant::now();
     let part_one = solve_part_one(processed.clone());
     let time_one = part_one_start.elapsed();
+    let usage_one = memory_one.finish();
     println!(
         "Part One: {:?} (Took {:?})",
         part_one,
         format_duration(time_one).to_string()
     );
+    report(usage_one);
 
+    let memory_two = Phase::start();
     let part_two_start = Instant::now();
     let part_two = solve_part_two(processed);
     let time_two = part_two_start.elapsed();
+    let usage_two = memory_two.finish();
 
     println!(
         "Part Two: {:?} (Took {:?})",
         part_two,
         format_duration(time_two).to_string()
     );
+    report(usage_two);
     Ok(())
 }
 
@@ -0,0 +1,118 @@
+use std::fmt::Display;
+
+use anyhow::Result;
+
+use crate::{
+    operator::{Failure, Operator},
+    Equation,
+};
+
+/// An audit of a single calibration line.
+pub struct Explanation<'a> {
+    equation: &'a Equation,
+    solution: Option<Vec<&'a dyn Operator>>,
+    valid_assignments: usize,
+    overflowed: bool,
+}
+
+impl<'a> Explanation<'a> {
+    /// Evaluates every operator assignment left to right, counting those that
+    /// hit the lhs and noting whether any intermediate value overflowed.
+    pub fn new(equation: &'a Equation, operators: &'a [Box<dyn Operator>]) -> Result<Self> {
+        let solution = equation.solve(operators)?;
+        let mut explanation = Explanation {
+            equation,
+            solution,
+            valid_assignments: 0,
+            overflowed: false,
+        };
+        // solve has already rejected an empty rhs.
+        let (first, rest) = equation.rhs.split_first().unwrap();
+        explanation.count_forwards(*first, rest, operators);
+        Ok(explanation)
+    }
+
+    fn count_forwards(&mut self, total: i64, operands: &[i64], operators: &[Box<dyn Operator>]) {
+        let Some((&next, rest)) = operands.split_first() else {
+            if total == self.equation.lhs {
+                self.valid_assignments += 1;
+            }
+            return;
+        };
+        for operator in operators {
+            match operator.apply(total, next) {
+                Ok(value) => self.count_forwards(value, rest, operators),
+                Err(Failure::Overflow) => self.overflowed = true,
+                // Undefined assignments, like concatenating negatives, just don't count.
+                Err(Failure::Undefined) => {}
+            }
+        }
+    }
+}
+
+impl Display for Explanation<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: ", self.equation.lhs)?;
+        match &self.solution {
+            Some(operators) => {
+                write!(f, "{}", self.equation.rhs[0])?;
+                for (operator, num) in operators.iter().zip(&self.equation.rhs[1..]) {
+                    write!(f, " {} {}", operator.symbol(), num)?;
+                }
+            }
+            None => write!(f, "no solution")?,
+        }
+        write!(f, " ({} valid assignments)", self.valid_assignments)?;
+        if self.overflowed {
+            write!(f, " [overflowed i64]")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::operator::{Add, Concatenate, Multiply};
+
+    fn explain(equation: &str, operators: &[Box<dyn Operator>]) -> String {
+        let equation: Equation = equation.parse().unwrap();
+        Explanation::new(&equation, operators).unwrap().to_string()
+    }
+
+    #[test]
+    fn agrees_with_the_solver() {
+        let operators: [Box<dyn Operator>; 2] = [Box::new(Add), Box::new(Multiply)];
+        assert_eq!(
+            explain("0: 5 0", &operators),
+            "0: 5 * 0 (1 valid assignments)"
+        );
+        assert_eq!(
+            explain("3267: 81 40 27", &operators),
+            "3267: 81 * 40 + 27 (2 valid assignments)"
+        );
+        assert_eq!(
+            explain("83: 17 5", &operators),
+            "83: no solution (0 valid assignments)"
+        );
+    }
+
+    #[test]
+    fn only_flags_real_overflow() {
+        let operators: [Box<dyn Operator>; 2] = [Box::new(Add), Box::new(Concatenate)];
+        assert_eq!(
+            explain("1: -5 6", &operators),
+            "1: -5 + 6 (1 valid assignments)"
+        );
+
+        let operators: [Box<dyn Operator>; 2] = [Box::new(Add), Box::new(Multiply)];
+        assert_eq!(
+            explain("9223372036854775807: 9223372036854775806 1", &operators),
+            "9223372036854775807: 9223372036854775806 + 1 (1 valid assignments)"
+        );
+        assert_eq!(
+            explain("1: 9223372036854775807 2", &operators),
+            "1: no solution (0 valid assignments) [overflowed i64]"
+        );
+    }
+}
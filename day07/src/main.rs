@@ -1,9 +1,14 @@
 use anyhow::anyhow;
+use anyhow::bail;
 use anyhow::Result;
+use explain::Explanation;
 use humantime::format_duration;
+use operator::{operator_from_symbol, Add, Concatenate, Inverse, Multiply, Operator};
 use rayon::iter::IntoParallelRefIterator;
 use rayon::iter::ParallelIterator;
-use std::{fs::read_to_string, str::FromStr, time::Instant};
+use std::{env, fs::read_to_string, str::FromStr, time::Instant};
+mod explain;
+mod operator;
 
 type ProcessedInput = Vec<Equation>;
 type Output = i64;
@@ -19,43 +24,77 @@ impl Equation {
         Self { lhs, rhs }
     }
 
-    fn solve(&self, solve_method: &dyn Fn(Vec<i64>, i64) -> Vec<i64>) -> Result<bool> {
-        let mut rhs_iter = self.rhs.clone().into_iter();
-        let mut options = vec![rhs_iter
-            .next()
-            .ok_or(anyhow!("RHS Must contain at least one element"))?];
-        for num in rhs_iter {
-            options = solve_method(options, num);
+    /// Searches for operators that make the rhs evaluate to the lhs.
+    /// Returns the operators in the order they appear between the numbers,
+    /// stopping at the first sequence found.
+    fn solve<'a>(
+        &self,
+        operators: &'a [Box<dyn Operator>],
+    ) -> Result<Option<Vec<&'a dyn Operator>>> {
+        if self.rhs.is_empty() {
+            bail!("RHS Must contain at least one element");
         }
-        Ok(options.contains(&self.lhs))
+        let mut found = vec![];
+        if solve_backwards(self.lhs, &self.rhs, operators, &mut found) {
+            return Ok(Some(found));
+        }
+        Ok(None)
     }
+}
 
-    fn recursive_part_one(left_options: Vec<i64>, next: i64) -> Vec<i64> {
-        let mut options = vec![];
-        for left_opt in left_options {
-            let addition = left_opt.checked_add(next);
-            let multiplication = left_opt.checked_mul(next);
-            if let Some(a) = addition {
-                options.push(a);
-            }
-            if let Some(m) = multiplication {
-                options.push(m);
-            }
+/// Undoes the last operator for each candidate, pruning any that can't be
+/// inverted exactly, until only the first operand remains.
+fn solve_backwards<'a>(
+    target: i64,
+    operands: &[i64],
+    operators: &'a [Box<dyn Operator>],
+    found: &mut Vec<&'a dyn Operator>,
+) -> bool {
+    let Some((&last, rest)) = operands.split_last() else {
+        return false;
+    };
+    if rest.is_empty() {
+        return last == target;
+    }
+    for operator in operators {
+        let solved = match operator.invert(target, last) {
+            Inverse::Candidates(lefts) => lefts
+                .into_iter()
+                .any(|left| solve_backwards(left, rest, operators, found)),
+            // There's no target left to work back from, so look for anything
+            // the rest evaluates to instead.
+            Inverse::Any => evaluates_forwards(rest[0], &rest[1..], operators, found),
+        };
+        if solved {
+            found.push(operator.as_ref());
+            return true;
         }
-        options
     }
+    false
+}
 
-    fn recursive_part_two(left_options: Vec<i64>, next: i64) -> Vec<i64> {
-        let mut options = Equation::recursive_part_one(left_options.clone(), next);
-        for left_opt in left_options {
-            let mut combination = left_opt.to_string();
-            combination.push_str(&next.to_string());
-            if let Ok(c) = combination.parse() {
-                options.push(c);
-            }
+/// Finds operators that evaluate `operands` onto `total` without
+/// overflowing or hitting anything undefined.
+fn evaluates_forwards<'a>(
+    total: i64,
+    operands: &[i64],
+    operators: &'a [Box<dyn Operator>],
+    found: &mut Vec<&'a dyn Operator>,
+) -> bool {
+    let Some((&next, rest)) = operands.split_first() else {
+        return true;
+    };
+    for operator in operators {
+        let Ok(value) = operator.apply(total, next) else {
+            continue;
+        };
+        found.push(operator.as_ref());
+        if evaluates_forwards(value, rest, operators, found) {
+            return true;
         }
-        options
+        found.pop();
     }
+    false
 }
 
 impl FromStr for Equation {
@@ -74,6 +113,7 @@ impl FromStr for Equation {
 }
 
 fn main() -> Result<()> {
+    let args = Args::parse(env::args().skip(1))?;
     let input = read_to_string("input.txt")?;
     println!("Lines in Input: {}", input.lines().count());
 
@@ -95,16 +135,74 @@ fn main() -> Result<()> {
     );
 
     let part_two_start = Instant::now();
-    let part_two = solve_part_two(processed);
+    let part_two = solve_part_two(processed.clone());
     let time_two = part_two_start.elapsed();
     println!(
         "Part Two: {:?} (Took {:?})",
         part_two,
         format_duration(time_two).to_string()
     );
+
+    if let Some(operators) = &args.operators {
+        let custom_start = Instant::now();
+        let custom = sum_solvable(&processed, operators);
+        let time_custom = custom_start.elapsed();
+        let symbols: Vec<_> = operators.iter().map(|o| o.symbol()).collect();
+        println!(
+            "Custom [{}]: {:?} (Took {:?})",
+            symbols.join(" "),
+            custom,
+            format_duration(time_custom).to_string()
+        );
+    }
+
+    if args.explain {
+        let operators = args
+            .operators
+            .unwrap_or_else(|| vec![Box::new(Add), Box::new(Multiply), Box::new(Concatenate)]);
+        let explanations = processed
+            .par_iter()
+            .map(|e| Explanation::new(e, &operators))
+            .collect::<Result<Vec<_>>>()?;
+        for explanation in explanations {
+            println!("{explanation}");
+        }
+    }
     Ok(())
 }
 
+/// Usage: day07 [--operators +,*,||,-,^,**] [--explain]
+struct Args {
+    operators: Option<Vec<Box<dyn Operator>>>,
+    explain: bool,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Args {
+            operators: None,
+            explain: false,
+        };
+        while let Some(flag) = args.next() {
+            match flag.as_str() {
+                "--operators" => {
+                    let symbols = args
+                        .next()
+                        .ok_or(anyhow!("Missing value for argument {flag}"))?;
+                    let operators = symbols
+                        .split(',')
+                        .map(operator_from_symbol)
+                        .collect::<Result<Vec<_>>>()?;
+                    parsed.operators = Some(operators);
+                }
+                "--explain" => parsed.explain = true,
+                _ => bail!("Unknown argument {flag}"),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
 fn process_input(input: String) -> Result<ProcessedInput> {
     input
         .lines()
@@ -114,17 +212,55 @@ fn process_input(input: String) -> Result<ProcessedInput> {
 }
 
 fn solve_part_one(data: ProcessedInput) -> Result<Output> {
-    Ok(data
-        .par_iter()
-        .filter(|e| e.solve(&Equation::recursive_part_one).is_ok_and(|v| v))
-        .map(|e| e.lhs)
-        .sum())
+    let operators: [Box<dyn Operator>; 2] = [Box::new(Add), Box::new(Multiply)];
+    sum_solvable(&data, &operators)
 }
 
 fn solve_part_two(data: ProcessedInput) -> Result<Output> {
+    let operators: [Box<dyn Operator>; 3] =
+        [Box::new(Add), Box::new(Multiply), Box::new(Concatenate)];
+    sum_solvable(&data, &operators)
+}
+
+fn sum_solvable(data: &ProcessedInput, operators: &[Box<dyn Operator>]) -> Result<Output> {
     Ok(data
         .par_iter()
-        .filter(|e| e.solve(&Equation::recursive_part_two).is_ok_and(|v| v))
+        .filter(|e| e.solve(operators).is_ok_and(|v| v.is_some()))
         .map(|e| e.lhs)
         .sum())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use operator::Power;
+
+    fn symbols(equation: &str, operators: &[Box<dyn Operator>]) -> Option<Vec<&'static str>> {
+        let equation: Equation = equation.parse().unwrap();
+        let found = equation.solve(operators).unwrap()?;
+        Some(found.iter().map(|o| o.symbol()).collect())
+    }
+
+    #[test]
+    fn solves_the_example() {
+        let input = "190: 10 19\n3267: 81 40 27\n83: 17 5\n156: 15 6\n7290: 6 8 6 15\n161011: 16 10 13\n192: 17 8 14\n21037: 9 7 18 13\n292: 11 6 16 20\n";
+        let data = process_input(input.to_string()).unwrap();
+        assert_eq!(solve_part_one(data.clone()).unwrap(), 3749);
+        assert_eq!(solve_part_two(data).unwrap(), 11387);
+    }
+
+    #[test]
+    fn solves_through_zero_operands() {
+        let operators: [Box<dyn Operator>; 2] = [Box::new(Add), Box::new(Multiply)];
+        assert_eq!(symbols("0: 5 0", &operators), Some(vec!["*"]));
+        assert_eq!(symbols("5: 5 0", &operators), Some(vec!["+"]));
+        assert_eq!(symbols("0: 3 4 0", &operators), Some(vec!["+", "*"]));
+        assert_eq!(symbols("6: 3 4 0 6", &operators), Some(vec!["+", "*", "+"]));
+        assert_eq!(symbols("1: 5 0", &operators), None);
+
+        let powers: [Box<dyn Operator>; 2] = [Box::new(Add), Box::new(Power)];
+        assert_eq!(symbols("1: 7 0", &powers), Some(vec!["**"]));
+        assert_eq!(symbols("3: 7 2 0 2", &powers), Some(vec!["+", "**", "+"]));
+        assert_eq!(symbols("17: -4 2 1", &powers), Some(vec!["**", "+"]));
+    }
+}
@@ -0,0 +1,309 @@
+use anyhow::{bail, Result};
+
+/// A binary operator that can appear between the numbers of an equation.
+/// Equations are evaluated strictly left to right, so the solver works
+/// backwards from the target by undoing the last operator each step.
+pub trait Operator: Send + Sync {
+    fn symbol(&self) -> &'static str;
+
+    /// Returns `left <op> right`, or why it couldn't be worked out.
+    fn apply(&self, left: i64, right: i64) -> Result<i64, Failure>;
+
+    /// Given `result = left <op> right` and `right`, returns every `left`
+    /// that could have produced `result`.
+    fn invert(&self, result: i64, right: i64) -> Inverse;
+}
+
+/// Why applying an operator gave no value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Failure {
+    /// The result doesn't fit in an i64.
+    Overflow,
+    /// The operator isn't defined for these operands, e.g. a negative exponent.
+    Undefined,
+}
+
+/// The possible left operands found by undoing an operator.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Inverse {
+    /// Every `left` that produces the result, usually one or none.
+    Candidates(Vec<i64>),
+    /// Every `left` produces the result, as with `x * 0` or `x ** 0`, so the
+    /// left hand side only has to evaluate to something.
+    Any,
+}
+
+impl From<Option<i64>> for Inverse {
+    fn from(left: Option<i64>) -> Self {
+        Inverse::Candidates(left.into_iter().collect())
+    }
+}
+
+pub struct Add;
+pub struct Multiply;
+pub struct Concatenate;
+pub struct Subtract;
+pub struct Xor;
+pub struct Power;
+
+impl Operator for Add {
+    fn symbol(&self) -> &'static str {
+        "+"
+    }
+
+    fn apply(&self, left: i64, right: i64) -> Result<i64, Failure> {
+        left.checked_add(right).ok_or(Failure::Overflow)
+    }
+
+    fn invert(&self, result: i64, right: i64) -> Inverse {
+        result.checked_sub(right).into()
+    }
+}
+
+impl Operator for Multiply {
+    fn symbol(&self) -> &'static str {
+        "*"
+    }
+
+    fn apply(&self, left: i64, right: i64) -> Result<i64, Failure> {
+        left.checked_mul(right).ok_or(Failure::Overflow)
+    }
+
+    /// Only exact division can be undone. Multiplying by zero loses `left`
+    /// entirely, so any `left` will do if the result is zero.
+    fn invert(&self, result: i64, right: i64) -> Inverse {
+        if right == 0 {
+            return match result {
+                0 => Inverse::Any,
+                _ => Inverse::Candidates(vec![]),
+            };
+        }
+        // `i64::MIN / -1` is out of range, so nothing times -1 makes it.
+        match result.checked_rem(right) {
+            Some(0) => result.checked_div(right).into(),
+            _ => Inverse::Candidates(vec![]),
+        }
+    }
+}
+
+impl Operator for Concatenate {
+    fn symbol(&self) -> &'static str {
+        "||"
+    }
+
+    fn apply(&self, left: i64, right: i64) -> Result<i64, Failure> {
+        if left < 0 || right < 0 {
+            return Err(Failure::Undefined);
+        }
+        digit_magnitude(right)
+            .and_then(|magnitude| left.checked_mul(magnitude))
+            .and_then(|shifted| shifted.checked_add(right))
+            .ok_or(Failure::Overflow)
+    }
+
+    /// Strips the digits of `right` off the end of `result`.
+    fn invert(&self, result: i64, right: i64) -> Inverse {
+        if result < 0 || right < 0 {
+            return Inverse::Candidates(vec![]);
+        }
+        digit_magnitude(right)
+            .filter(|magnitude| result % magnitude == right)
+            .map(|magnitude| result / magnitude)
+            .into()
+    }
+}
+
+impl Operator for Subtract {
+    fn symbol(&self) -> &'static str {
+        "-"
+    }
+
+    fn apply(&self, left: i64, right: i64) -> Result<i64, Failure> {
+        left.checked_sub(right).ok_or(Failure::Overflow)
+    }
+
+    fn invert(&self, result: i64, right: i64) -> Inverse {
+        result.checked_add(right).into()
+    }
+}
+
+impl Operator for Xor {
+    fn symbol(&self) -> &'static str {
+        "^"
+    }
+
+    fn apply(&self, left: i64, right: i64) -> Result<i64, Failure> {
+        Ok(left ^ right)
+    }
+
+    fn invert(&self, result: i64, right: i64) -> Inverse {
+        Some(result ^ right).into()
+    }
+}
+
+impl Operator for Power {
+    fn symbol(&self) -> &'static str {
+        "**"
+    }
+
+    /// Negative exponents would need fractions, so they're undefined.
+    fn apply(&self, left: i64, right: i64) -> Result<i64, Failure> {
+        if right < 0 {
+            return Err(Failure::Undefined);
+        }
+        match u32::try_from(right) {
+            Ok(exponent) => left.checked_pow(exponent).ok_or(Failure::Overflow),
+            Err(_) => match left {
+                0 | 1 => Ok(left),
+                -1 => Ok(if right % 2 == 0 { 1 } else { -1 }),
+                _ => Err(Failure::Overflow),
+            },
+        }
+    }
+
+    /// Takes the exact integer `right`th roots of `result`, both signs of
+    /// them for even exponents. Anything to the power of zero is one.
+    fn invert(&self, result: i64, right: i64) -> Inverse {
+        let Ok(exponent) = u32::try_from(right) else {
+            return Inverse::Candidates(vec![]);
+        };
+        if exponent == 0 {
+            return match result {
+                1 => Inverse::Any,
+                _ => Inverse::Candidates(vec![]),
+            };
+        }
+        if result < 0 && exponent % 2 == 0 {
+            return Inverse::Candidates(vec![]);
+        }
+        let Some(root) = exact_root(result.unsigned_abs(), exponent) else {
+            return Inverse::Candidates(vec![]);
+        };
+        let candidates = match (result < 0, exponent % 2 == 0) {
+            // The root of 2^63 is only representable negated.
+            (true, _) => vec![0_i64.checked_sub_unsigned(root)],
+            (false, true) if root > 0 => vec![i64::try_from(root).ok(), Some(-(root as i64))],
+            (false, _) => vec![i64::try_from(root).ok()],
+        };
+        Inverse::Candidates(candidates.into_iter().flatten().collect())
+    }
+}
+
+/// The whole number whose `exponent`th power is exactly `value`, if any.
+fn exact_root(value: u64, exponent: u32) -> Option<u64> {
+    if exponent == 1 {
+        return Some(value);
+    }
+    let (mut low, mut high) = (0_u64, value.min(1 << (64 / exponent + 1)));
+    while low <= high {
+        let mid = low + (high - low) / 2;
+        match mid.checked_pow(exponent).map(|v| v.cmp(&value)) {
+            Some(std::cmp::Ordering::Equal) => return Some(mid),
+            Some(std::cmp::Ordering::Less) => low = mid + 1,
+            _ => high = mid.checked_sub(1)?,
+        }
+    }
+    None
+}
+
+/// The smallest power of ten greater than `number`, i.e. what a number has
+/// to be multiplied by to make room for the digits of `number`.
+fn digit_magnitude(number: i64) -> Option<i64> {
+    let mut magnitude: i64 = 10;
+    while magnitude <= number {
+        magnitude = magnitude.checked_mul(10)?;
+    }
+    Some(magnitude)
+}
+
+pub fn operator_from_symbol(symbol: &str) -> Result<Box<dyn Operator>> {
+    let operator: Box<dyn Operator> = match symbol {
+        "+" => Box::new(Add),
+        "*" => Box::new(Multiply),
+        "||" => Box::new(Concatenate),
+        "-" => Box::new(Subtract),
+        "^" => Box::new(Xor),
+        "**" => Box::new(Power),
+        _ => bail!("Unknown operator {symbol}"),
+    };
+    Ok(operator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(lefts: &[i64]) -> Inverse {
+        Inverse::Candidates(lefts.to_vec())
+    }
+
+    #[test]
+    fn inverts_each_operator() {
+        assert_eq!(Add.invert(10, 4), candidates(&[6]));
+        assert_eq!(Add.invert(i64::MIN, 1), candidates(&[]));
+        assert_eq!(Subtract.invert(10, 4), candidates(&[14]));
+        assert_eq!(Xor.invert(6, 3), candidates(&[5]));
+
+        assert_eq!(Multiply.invert(12, 4), candidates(&[3]));
+        assert_eq!(Multiply.invert(-12, 4), candidates(&[-3]));
+        assert_eq!(Multiply.invert(13, 4), candidates(&[]));
+        assert_eq!(Multiply.invert(0, 0), Inverse::Any);
+        assert_eq!(Multiply.invert(5, 0), candidates(&[]));
+        assert_eq!(Multiply.invert(i64::MIN, -1), candidates(&[]));
+        assert_eq!(Multiply.invert(i64::MIN, 1), candidates(&[i64::MIN]));
+
+        assert_eq!(Concatenate.invert(156, 6), candidates(&[15]));
+        assert_eq!(Concatenate.invert(1510, 10), candidates(&[15]));
+        assert_eq!(Concatenate.invert(10, 0), candidates(&[1]));
+        assert_eq!(Concatenate.invert(156, 7), candidates(&[]));
+        assert_eq!(Concatenate.invert(-156, 6), candidates(&[]));
+
+        assert_eq!(Power.invert(81, 4), candidates(&[3, -3]));
+        assert_eq!(Power.invert(-8, 3), candidates(&[-2]));
+        assert_eq!(Power.invert(i64::MIN, 63), candidates(&[-2]));
+        assert_eq!(Power.invert(0, 2), candidates(&[0]));
+        assert_eq!(Power.invert(-4, 2), candidates(&[]));
+        assert_eq!(Power.invert(80, 4), candidates(&[]));
+        assert_eq!(Power.invert(7, 1), candidates(&[7]));
+        assert_eq!(Power.invert(1, 0), Inverse::Any);
+        assert_eq!(Power.invert(2, 0), candidates(&[]));
+        assert_eq!(Power.invert(2, -1), candidates(&[]));
+    }
+
+    #[test]
+    fn tells_overflow_from_undefined() {
+        assert_eq!(Add.apply(i64::MAX, 1), Err(Failure::Overflow));
+        assert_eq!(Multiply.apply(i64::MAX, 2), Err(Failure::Overflow));
+        assert_eq!(Subtract.apply(i64::MIN, 1), Err(Failure::Overflow));
+        assert_eq!(Concatenate.apply(-1, 2), Err(Failure::Undefined));
+        assert_eq!(Concatenate.apply(1, -2), Err(Failure::Undefined));
+        assert_eq!(Concatenate.apply(i64::MAX / 10, 99), Err(Failure::Overflow));
+        assert_eq!(Power.apply(2, -1), Err(Failure::Undefined));
+        assert_eq!(Power.apply(2, 63), Err(Failure::Overflow));
+        assert_eq!(Power.apply(2, i64::MAX), Err(Failure::Overflow));
+        assert_eq!(Power.apply(-1, i64::MAX), Ok(-1));
+        assert_eq!(Power.apply(-2, 63), Ok(i64::MIN));
+    }
+
+    #[test]
+    fn every_candidate_applies_back() {
+        let operators = ["+", "*", "||", "-", "^", "**"].map(|s| operator_from_symbol(s).unwrap());
+        for operator in &operators {
+            for result in -70..70 {
+                for right in -3..12 {
+                    let Inverse::Candidates(lefts) = operator.invert(result, right) else {
+                        continue;
+                    };
+                    for left in lefts {
+                        assert_eq!(
+                            operator.apply(left, right),
+                            Ok(result),
+                            "{left} {} {right}",
+                            operator.symbol()
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::grid::coordinate::Coordinate;
+
+/// Where a pair of same-frequency antennas creates antinodes.
+#[derive(Clone, Debug)]
+pub enum AntinodeRule {
+    /// The puzzle's part one: the points just beyond each antenna where one
+    /// antenna is twice as far away as the other.
+    DoubleDistance,
+    /// Every grid point exactly in line with the pair. The step between
+    /// points is the pair's offset divided by its gcd, so none are skipped.
+    Collinear,
+    /// Every grid point in line with the pair whose distances to the two
+    /// antennas are in one of the ratios `p:q`, both between and beyond them.
+    Ratios(Vec<(i64, i64)>),
+}
+
+impl AntinodeRule {
+    /// Parses a comma separated list of ratios such as "2:1,3:1".
+    pub fn parse_ratios(value: &str) -> Result<Self> {
+        let mut ratios = vec![];
+        for ratio in value.split(',') {
+            let (p, q) = ratio
+                .split_once(':')
+                .ok_or(anyhow!("Ratio must look like P:Q, got {ratio}"))?;
+            let (p, q): (i64, i64) = (p.trim().parse()?, q.trim().parse()?);
+            if p <= 0 || q <= 0 {
+                bail!("Ratio parts must be positive, got {ratio}");
+            }
+            ratios.push((p, q));
+        }
+        Ok(AntinodeRule::Ratios(ratios))
+    }
+}
+
+/// Finds the antinodes of every frequency on a `rows` by `cols` grid.
+pub fn find_antinodes(
+    frequencies: &HashMap<char, Vec<Coordinate>>,
+    rows: usize,
+    cols: usize,
+    rule: &AntinodeRule,
+) -> HashMap<char, HashSet<Coordinate>> {
+    let bounds = Bounds { rows, cols };
+    let mut antinodes = HashMap::new();
+    for (&frequency, positions) in frequencies {
+        let found: &mut HashSet<Coordinate> = antinodes.entry(frequency).or_default();
+        for (i, &a) in positions.iter().enumerate() {
+            for &b in &positions[i + 1..] {
+                found.extend(pair_antinodes(a, b, &bounds, rule));
+            }
+        }
+    }
+    antinodes
+}
+
+struct Bounds {
+    rows: usize,
+    cols: usize,
+}
+
+impl Bounds {
+    fn contains(&self, point: (i64, i64)) -> Option<Coordinate> {
+        let row = usize::try_from(point.0).ok()?;
+        let col = usize::try_from(point.1).ok()?;
+        (row < self.rows && col < self.cols).then_some(Coordinate::new(row, col))
+    }
+}
+
+fn pair_antinodes(
+    a: Coordinate,
+    b: Coordinate,
+    bounds: &Bounds,
+    rule: &AntinodeRule,
+) -> Vec<Coordinate> {
+    let a = (a.row as i64, a.col as i64);
+    let b = (b.row as i64, b.col as i64);
+    let delta = (b.0 - a.0, b.1 - a.1);
+    match rule {
+        AntinodeRule::DoubleDistance => [
+            (a.0 - delta.0, a.1 - delta.1),
+            (b.0 + delta.0, b.1 + delta.1),
+        ]
+        .into_iter()
+        .filter_map(|p| bounds.contains(p))
+        .collect(),
+        AntinodeRule::Collinear => {
+            let divisor = gcd(delta.0.abs(), delta.1.abs());
+            let step = (delta.0 / divisor, delta.1 / divisor);
+            let mut points = vec![];
+            for direction in [1, -1] {
+                let mut point = a;
+                while let Some(pos) = bounds.contains(point) {
+                    points.push(pos);
+                    point = (point.0 + direction * step.0, point.1 + direction * step.1);
+                }
+            }
+            points
+        }
+        AntinodeRule::Ratios(ratios) => ratios
+            .iter()
+            .flat_map(|&(p, q)| {
+                ratio_points(a, b, p, q)
+                    .into_iter()
+                    .chain(ratio_points(b, a, p, q))
+            })
+            .filter_map(|p| bounds.contains(p))
+            .collect(),
+    }
+}
+
+/// Points P in line with A and B where |PA| : |PB| = p : q, kept only when
+/// they land exactly on the grid. The point between them is (qA + pB)/(p+q)
+/// and, unless p = q, the point beyond them is (pB - qA)/(p-q). Ratios can be
+/// as large as any i64, so the sums are worked out in i128 where they fit.
+fn ratio_points(a: (i64, i64), b: (i64, i64), p: i64, q: i64) -> Vec<(i64, i64)> {
+    let (a, b) = ((a.0 as i128, a.1 as i128), (b.0 as i128, b.1 as i128));
+    let (p, q) = (p as i128, q as i128);
+    let mut points = vec![];
+    let mut push_exact = |numerator: (i128, i128), denominator: i128| {
+        if numerator.0 % denominator == 0 && numerator.1 % denominator == 0 {
+            let row = i64::try_from(numerator.0 / denominator);
+            let col = i64::try_from(numerator.1 / denominator);
+            if let (Ok(row), Ok(col)) = (row, col) {
+                points.push((row, col));
+            }
+        }
+    };
+    push_exact((q * a.0 + p * b.0, q * a.1 + p * b.1), p + q);
+    if p != q {
+        push_exact((p * b.0 - q * a.0, p * b.1 - q * a.1), p - q);
+    }
+    points
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn antinodes(
+        a: (usize, usize),
+        b: (usize, usize),
+        size: usize,
+        rule: &AntinodeRule,
+    ) -> Vec<(usize, usize)> {
+        let bounds = Bounds {
+            rows: size,
+            cols: size,
+        };
+        let a = Coordinate::new(a.0, a.1);
+        let b = Coordinate::new(b.0, b.1);
+        let mut points: Vec<(usize, usize)> = pair_antinodes(a, b, &bounds, rule)
+            .into_iter()
+            .map(|c| (c.row, c.col))
+            .collect();
+        points.sort();
+        points.dedup();
+        points
+    }
+
+    #[test]
+    fn doubles_the_distance() {
+        let rule = AntinodeRule::DoubleDistance;
+        assert_eq!(antinodes((3, 4), (5, 5), 10, &rule), vec![(1, 3), (7, 6)]);
+        assert_eq!(antinodes((0, 0), (1, 1), 10, &rule), vec![(2, 2)]);
+    }
+
+    #[test]
+    fn collinear_steps_by_the_reduced_offset() {
+        let rule = AntinodeRule::Collinear;
+        assert_eq!(
+            antinodes((1, 1), (3, 5), 6, &rule),
+            vec![(1, 1), (2, 3), (3, 5)]
+        );
+        assert_eq!(
+            antinodes((2, 2), (4, 4), 6, &rule),
+            vec![(0, 0), (1, 1), (2, 2), (3, 3), (4, 4), (5, 5)]
+        );
+    }
+
+    #[test]
+    fn ratios_give_points_between_and_beyond() {
+        let rule = AntinodeRule::parse_ratios("2:1").unwrap();
+        // (2, 2) and (1, 1) lie between, (6, 6) beyond, and (-3, -3) is
+        // off the grid.
+        assert_eq!(
+            antinodes((0, 0), (3, 3), 10, &rule),
+            vec![(1, 1), (2, 2), (6, 6)]
+        );
+        // 1:1 is only the midpoint, and only when it's on a grid point.
+        let rule = AntinodeRule::parse_ratios("1:1").unwrap();
+        assert_eq!(antinodes((0, 0), (2, 4), 10, &rule), vec![(1, 2)]);
+        assert_eq!(antinodes((0, 0), (1, 4), 10, &rule), vec![]);
+    }
+
+    #[test]
+    fn huge_ratios_dont_overflow() {
+        let rule = AntinodeRule::parse_ratios("9223372036854775807:1").unwrap();
+        assert_eq!(antinodes((0, 0), (3, 3), 10, &rule), vec![]);
+        // Equal parts are the midpoint however large they are.
+        let rule = AntinodeRule::parse_ratios("9223372036854775807:9223372036854775807").unwrap();
+        assert_eq!(antinodes((0, 0), (2, 2), 10, &rule), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn parses_ratios() {
+        let AntinodeRule::Ratios(ratios) = AntinodeRule::parse_ratios("2:1, 3 : 1").unwrap() else {
+            panic!("Expected ratios");
+        };
+        assert_eq!(ratios, vec![(2, 1), (3, 1)]);
+        for bad in ["", "2", "2:", "2:0", "-1:2", "a:1", "2:1,", "1:2:3"] {
+            assert!(AntinodeRule::parse_ratios(bad).is_err(), "accepted {bad:?}");
+        }
+    }
+}
@@ -0,0 +1,11 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Coordinate {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Coordinate {
+    pub fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+}
@@ -0,0 +1,2 @@
+pub mod coordinate;
+pub mod map;
@@ -1,37 +1,47 @@
-use anyhow::{anyhow, Result};
-use grid::Grid;
+use antinode::{find_antinodes, AntinodeRule};
+use anyhow::{anyhow, bail, Result};
+use grid::{coordinate::Coordinate, map::Map};
 use humantime::format_duration;
-use std::{cell, collections::HashMap, fs::read_to_string, time::Instant};
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    fs::read_to_string,
+    time::Instant,
+};
+mod antinode;
+mod grid;
 
-type ProcessedInput = Map;
+type ProcessedInput = City;
 type Output = usize;
 
+/// The antenna map along with where each frequency's antennas are.
 #[derive(Clone)]
-struct Map {
-    cells: Grid<ACell>,
-    frequencies: HashMap<char, Vec<(usize, usize)>>,
+struct City {
+    map: Map<ACell>,
+    frequencies: HashMap<char, Vec<Coordinate>>,
 }
 
-impl Map {
-    fn new(cells: Grid<ACell>, frequencies: HashMap<char, Vec<(usize, usize)>>) -> Self {
-        Self { cells, frequencies }
-    }
-
-    fn calculate_antinodes(&mut self, factor_harmonics: bool) {
-        for (_, positions) in self.frequencies.clone() {
-            for pos in positions.iter() {
-                self.update_antinode(pos, &positions, factor_harmonics);
+impl City {
+    fn new(map: Map<ACell>) -> Self {
+        let mut frequencies: HashMap<char, Vec<Coordinate>> = HashMap::new();
+        for (coord, cell) in &map {
+            if let Some(frequency) = cell.antenna {
+                frequencies.entry(frequency).or_default().push(coord);
             }
         }
-        println!("{}", self.print());
+        Self { map, frequencies }
+    }
+
+    fn antinodes(&self, rule: &AntinodeRule) -> HashMap<char, HashSet<Coordinate>> {
+        find_antinodes(&self.frequencies, self.map.rows(), self.map.cols(), rule)
     }
 
-    fn print(&self) -> String {
+    fn print(&self, antinodes: &HashSet<Coordinate>) -> String {
         let mut str = String::new();
-        for row in self.cells.iter_rows() {
-            for col in row {
-                let mut char = col.antenna.unwrap_or('.');
-                if char == '.' && col.antinode_present {
+        for (row, cells) in self.map.cells.iter_rows().enumerate() {
+            for (col, cell) in cells.enumerate() {
+                let mut char = cell.antenna.unwrap_or('.');
+                if char == '.' && antinodes.contains(&Coordinate::new(row, col)) {
                     char = '#';
                 }
                 str.push(char);
@@ -40,58 +50,20 @@ impl Map {
         }
         str
     }
+}
 
-    fn update_antinode(
-        &mut self,
-        pos: &(usize, usize),
-        positions: &Vec<(usize, usize)>,
-        factor_harmonics: bool,
-    ) {
-        for other_pos in positions {
-            self.update_antinode_sub(pos, other_pos, factor_harmonics);
-        }
-    }
-    fn update_antinode_sub(
-        &mut self,
-        pos1: &(usize, usize),
-        pos2: &(usize, usize),
-        factor_harmonics: bool,
-    ) {
-        if pos1 == pos2 {
-            return;
-        }
-        if factor_harmonics {
-            self.cells.get_mut(pos1.0, pos1.1).unwrap().antinode_present = true;
-        }
-        let delta_pos = (pos1.0 as i32 - pos2.0 as i32, pos1.1 as i32 - pos2.1 as i32);
-        let mut antinode_pos = (pos1.0 as i32, pos1.1 as i32);
-        loop {
-            antinode_pos = (antinode_pos.0 + delta_pos.0, antinode_pos.1 + delta_pos.1);
-            if antinode_pos.0 < 0 || antinode_pos.1 < 0 {
-                return;
-            }
-            let antinode_pos = (antinode_pos.0 as usize, antinode_pos.1 as usize);
-            if antinode_pos.0 >= self.cells.size().0 || antinode_pos.1 >= self.cells.size().1 {
-                return;
-            }
-            self.cells
-                .get_mut(antinode_pos.0, antinode_pos.1)
-                .unwrap()
-                .antinode_present = true;
-            if !factor_harmonics {
-                return;
-            }
-        }
-    }
+/// Every position that holds an antinode of any frequency.
+fn all_antinodes(antinodes: HashMap<char, HashSet<Coordinate>>) -> HashSet<Coordinate> {
+    antinodes.into_values().flatten().collect()
 }
 
 #[derive(Default, Clone)]
 struct ACell {
-    antinode_present: bool,
     antenna: Option<char>,
 }
 
 fn main() -> Result<()> {
+    let args = Args::parse(env::args().skip(1))?;
     let input = read_to_string("input.txt")?;
     println!("Lines in Input: {}", input.lines().count());
 
@@ -113,46 +85,111 @@ fn main() -> Result<()> {
     );
 
     let part_two_start = Instant::now();
-    let part_two = solve_part_two(processed);
+    let part_two = solve_part_two(processed.clone());
     let time_two = part_two_start.elapsed();
     println!(
         "Part Two: {:?} (Took {:?})",
         part_two,
         format_duration(time_two).to_string()
     );
+
+    if let Some(rule) = args.rule {
+        let antinodes = processed.antinodes(&rule);
+        let mut frequencies: Vec<_> = antinodes.iter().collect();
+        frequencies.sort_by_key(|(frequency, _)| **frequency);
+        for (frequency, positions) in frequencies {
+            println!("{frequency}: {} antinodes", positions.len());
+        }
+        let all = all_antinodes(antinodes);
+        println!("{rule:?}: {} antinodes", all.len());
+        if args.show {
+            println!("{}", processed.print(&all));
+        }
+    }
     Ok(())
 }
 
-fn process_input(input: String) -> Result<ProcessedInput> {
-    let width = input
-        .lines()
-        .next()
-        .ok_or(anyhow!("Input must contain at least one line"))?
-        .len();
-    let height = input.lines().filter(|e| !e.is_empty()).count();
-    let mut grid = Grid::new(width, height);
-    let mut frequencies: HashMap<char, Vec<(usize, usize)>> = HashMap::new();
-    for (row, str) in input.lines().filter(|e| !e.is_empty()).enumerate() {
-        for (col, char) in str.chars().enumerate() {
-            let cell: &mut ACell = grid.get_mut(row, col).unwrap();
-            cell.antinode_present = false;
-            if '.' == char {
-                cell.antenna = None;
-            } else {
-                frequencies.entry(char).or_default().push((row, col));
-                cell.antenna = Some(char);
+/// Usage: day08 [--rule double|collinear|P:Q,P:Q...] [--show]
+/// Runs an extra pass with the given antinode rule, listing the antinodes
+/// per frequency and optionally drawing them on the map.
+struct Args {
+    rule: Option<AntinodeRule>,
+    show: bool,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Args {
+            rule: None,
+            show: false,
+        };
+        while let Some(flag) = args.next() {
+            match flag.as_str() {
+                "--show" => parsed.show = true,
+                "--rule" => {
+                    let value = args
+                        .next()
+                        .ok_or(anyhow!("Missing value for argument {flag}"))?;
+                    parsed.rule = Some(match value.as_str() {
+                        "double" => AntinodeRule::DoubleDistance,
+                        "collinear" => AntinodeRule::Collinear,
+                        ratios => AntinodeRule::parse_ratios(ratios)?,
+                    });
+                }
+                _ => bail!("Unknown argument {flag}"),
             }
         }
+        Ok(parsed)
     }
-    Ok(Map::new(grid, frequencies))
 }
 
-fn solve_part_one(mut map: Map) -> Result<Output> {
-    map.calculate_antinodes(false);
-    Ok(map.cells.iter().filter(|e| e.antinode_present).count())
+fn process_input(input: String) -> Result<ProcessedInput> {
+    let map = Map::from_str(&input, |char| {
+        Ok(ACell {
+            antenna: (char != '.').then_some(char),
+        })
+    })?;
+    Ok(City::new(map))
+}
+
+fn solve_part_one(city: City) -> Result<Output> {
+    Ok(all_antinodes(city.antinodes(&AntinodeRule::DoubleDistance)).len())
+}
+
+fn solve_part_two(city: City) -> Result<Output> {
+    Ok(all_antinodes(city.antinodes(&AntinodeRule::Collinear)).len())
 }
 
-fn solve_part_two(mut map: Map) -> Result<Output> {
-    map.calculate_antinodes(true);
-    Ok(map.cells.iter().filter(|e| e.antinode_present).count())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Wider than it is tall, so swapping rows and columns loses antinodes.
+    const WIDE: &str = "..........\n...a.a....\n..........\n";
+
+    #[test]
+    fn non_square_dimensions() {
+        let city = process_input(WIDE.to_string()).unwrap();
+        assert_eq!((city.map.rows(), city.map.cols()), (3, 10));
+        assert_eq!(
+            city.frequencies[&'a'],
+            vec![Coordinate::new(1, 3), Coordinate::new(1, 5)]
+        );
+        assert_eq!(solve_part_one(city.clone()).unwrap(), 2);
+        assert_eq!(solve_part_two(city).unwrap(), 10);
+    }
+
+    #[test]
+    fn non_square_transposed() {
+        let tall: String = (0..10)
+            .map(|row| match row {
+                3 | 5 => ".a.\n",
+                _ => "...\n",
+            })
+            .collect();
+        let city = process_input(tall).unwrap();
+        assert_eq!((city.map.rows(), city.map.cols()), (10, 3));
+        assert_eq!(solve_part_one(city.clone()).unwrap(), 2);
+        assert_eq!(solve_part_two(city).unwrap(), 10);
+    }
 }
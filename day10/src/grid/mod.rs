@@ -0,0 +1,3 @@
+pub mod coordinate;
+pub mod direction;
+pub mod map;
@@ -1,19 +1,24 @@
-mod map;
-use anyhow::{anyhow, Result};
-use grid::Grid;
+mod grid;
+mod rules;
+mod trails;
+use anyhow::{anyhow, bail, Result};
+use grid::map::Map;
 use humantime::format_duration;
-use map::{Direction, Map};
-use std::{collections::HashSet, fs::read_to_string, time::Instant};
+use rules::{HikingRules, Neighbourhood};
+use std::{env, fs::read_to_string, time::Instant};
+use trails::{end_scores, path_counts, TrailMap, TrailPaths};
 
-type ProcessedInput = Map<u8>;
+type ProcessedInput = TrailMap;
 
 type Output = usize;
 
 fn main() -> Result<()> {
+    let args = Args::parse(env::args().skip(1))?;
+    let rules = args.rules;
     let string = read_to_string("input.txt")?;
 
     let time_proc = Instant::now();
-    let processed = process_input(string.clone())?;
+    let processed = process_input(string.clone(), &rules)?;
     let time_proc = format_duration(time_proc.elapsed());
     println!(
         "Processed input [{:?} lines] (Took: {})",
@@ -22,110 +27,199 @@ fn main() -> Result<()> {
     );
 
     let time_one = Instant::now();
-    let part_one = solve_part_one(processed.clone());
+    let part_one = solve_part_one(processed.clone(), &rules);
     let time_one = format_duration(time_one.elapsed());
 
     let time_two = Instant::now();
-    let part_two = solve_part_two(processed);
+    let part_two = solve_part_two(processed.clone(), &rules);
     let time_two = format_duration(time_two.elapsed());
 
     println!("Part One: {:?} (Took: {})", part_one, time_one);
     println!("Part Two: {:?} (Took: {})", part_two, time_two);
-    Ok(())
-}
 
-fn process_input(string: String) -> Result<ProcessedInput> {
-    let cols = string
-        .lines()
-        .find(|e| !e.is_empty())
-        .ok_or(anyhow!("Input must contain at least one line"))?
-        .len();
-    let rows = string.lines().filter(|e| !e.is_empty()).count();
-    let mut grid = Grid::new(rows, cols);
-    for (y, line) in string.lines().filter(|e| !e.is_empty()).enumerate() {
-        for (x, char) in line.chars().enumerate() {
-            let digit =
-                char.to_digit(10)
-                    .ok_or(anyhow!("All characters must be digits [0-9]"))? as u8;
-            // println!("{x} and {y}");
-            *grid.get_mut(x, y).unwrap() = digit;
+    if let Some(limit) = args.paths {
+        for path in TrailPaths::new(&processed, &rules).take(limit) {
+            let steps: Vec<String> = path
+                .iter()
+                .map(|pos| format!("({}, {})", pos.row, pos.col))
+                .collect();
+            println!("{}", steps.join(" -> "));
         }
     }
-    Ok(Map::new(grid))
+    Ok(())
 }
 
-fn solve_part_one(map: ProcessedInput) -> Result<Output> {
-    let trail_starters = map.cells.indexed_iter().filter(|(_, &f)| f == 0);
-    let mut count = 0;
-    for (start_pos, num) in trail_starters {
-        let trail_score = score_trails_part_one(start_pos, *num, &map).len();
-        count += trail_score
-    }
-    Ok(count)
+/// Usage: day10 [--paths LIMIT] [--start H] [--end H] [--deltas D,D...]
+///              [--diagonal] [--impassable CHARS]
+/// --paths lists up to LIMIT trails, walking them lazily. The rest change
+/// the hiking rules, e.g. `--start 9 --end 0 --deltas -1` walks downhill.
+struct Args {
+    paths: Option<usize>,
+    rules: HikingRules,
 }
 
-fn score_trails_part_one(
-    pos: (usize, usize),
-    num: u8,
-    map: &ProcessedInput,
-) -> HashSet<(usize, usize)> {
-    let mut hashset = HashSet::new();
-    if num == 9 {
-        hashset.insert(pos);
-        return hashset;
-    };
-    for dir in Direction::cardinals().into_iter() {
-        let cell = map.get_relative_cell(&pos, dir);
-        let (cell_pos, &new_num) = match cell {
-            Some(c) => c,
-            None => continue,
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Args {
+            paths: None,
+            rules: HikingRules::default(),
         };
-        if new_num != num + 1 {
-            continue;
+        while let Some(flag) = args.next() {
+            if flag == "--diagonal" {
+                parsed.rules.neighbourhood = Neighbourhood::Eight;
+                continue;
+            }
+            let value = args
+                .next()
+                .ok_or(anyhow!("Missing value for argument {flag}"))?;
+            match flag.as_str() {
+                "--paths" => parsed.paths = Some(value.parse()?),
+                "--start" => parsed.rules.start = value.parse()?,
+                "--end" => parsed.rules.end = value.parse()?,
+                "--deltas" => {
+                    parsed.rules.deltas = value
+                        .split(',')
+                        .map(|delta| delta.trim().parse())
+                        .collect::<Result<_, _>>()?
+                }
+                "--impassable" => parsed.rules.impassable = value.chars().collect(),
+                _ => bail!("Unknown argument {flag}"),
+            }
         }
-        // Cell is one higher, and cardinally adjacent.
-        hashset.extend(score_trails_part_one(cell_pos, new_num, map).iter());
+        parsed.rules.validate()?;
+        Ok(parsed)
     }
-    hashset
 }
 
-fn solve_part_two(map: ProcessedInput) -> Result<Output> {
-    let trail_starters = map.cells.indexed_iter().filter(|(_, &f)| f == 0);
-    let mut count = 0;
-    for (start_pos, num) in trail_starters {
-        let distinct_trails = score_trails_part_two(start_pos, *num, &map).len();
-        count += distinct_trails;
-    }
-    Ok(count)
+fn process_input(string: String, rules: &HikingRules) -> Result<ProcessedInput> {
+    Map::from_str(&string, |char| {
+        if rules.impassable.contains(&char) {
+            return Ok(None);
+        }
+        let digit = char.to_digit(10).ok_or(anyhow!(
+            "All characters must be digits [0-9] or impassable {:?}, got {char:?}",
+            rules.impassable
+        ))?;
+        Ok(Some(digit as u8))
+    })
+}
+
+fn solve_part_one(map: ProcessedInput, rules: &HikingRules) -> Result<Output> {
+    Ok(end_scores(&map, rules).values().sum())
 }
 
-type TrailPath = Vec<(usize, usize)>;
-
-fn score_trails_part_two(pos: (usize, usize), num: u8, map: &ProcessedInput) -> Vec<TrailPath> {
-    // println!("Scoring Part Two: at {:?}[{num}]", pos);
-    let mut paths: Vec<TrailPath> = Vec::new();
-    if num == 9 {
-        let final_path = vec![pos];
-        paths.push(final_path);
-        // println!("Found a 9, added positions to trailpath. [{:?}]", paths);
-        return paths;
-    };
-    for dir in Direction::cardinals().into_iter() {
-        let cell = map.get_relative_cell(&pos, dir);
-        let (cell_pos, &new_num) = match cell {
-            Some(c) => c,
-            None => continue,
+fn solve_part_two(map: ProcessedInput, rules: &HikingRules) -> Result<Output> {
+    let counts = path_counts(&map, rules);
+    Ok((&map)
+        .into_iter()
+        .filter(|(_, &height)| height == Some(rules.start))
+        .map(|(pos, _)| counts.get(&pos).unwrap())
+        .sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::coordinate::Coordinate;
+
+    const EXAMPLE: &str = "\
+89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732
+";
+
+    fn solve(input: &str, rules: &HikingRules) -> (Output, Output) {
+        let map = process_input(input.to_string(), rules).unwrap();
+        (
+            solve_part_one(map.clone(), rules).unwrap(),
+            solve_part_two(map, rules).unwrap(),
+        )
+    }
+
+    #[test]
+    fn example() {
+        let rules = HikingRules::default();
+        assert_eq!(solve(EXAMPLE, &rules), (36, 81));
+        let map = process_input(EXAMPLE.to_string(), &rules).unwrap();
+        let paths: Vec<_> = TrailPaths::new(&map, &rules).collect();
+        assert_eq!(paths.len(), 81);
+        assert!(paths.iter().all(|path| path.len() == 10));
+    }
+
+    #[test]
+    fn impassable_examples() {
+        let rules = HikingRules::default();
+        let score = "...0...\n...1...\n...2...\n6543456\n7.....7\n8.....8\n9.....9\n";
+        assert_eq!(solve(score, &rules).0, 2);
+        let rating = ".....0.\n..4321.\n..5..2.\n..6543.\n..7..4.\n..8765.\n..9....\n";
+        assert_eq!(solve(rating, &rules).1, 3);
+    }
+
+    #[test]
+    fn descending_trails_mirror_climbing_ones() {
+        let rules = HikingRules {
+            start: 9,
+            end: 0,
+            deltas: vec![-1],
+            ..HikingRules::default()
         };
-        if new_num != num + 1 {
-            continue;
-        }
-        // Cell is one higher, and cardinally adjacent. Add it to each TrailPath.
-        let mut new_paths = score_trails_part_two(cell_pos, new_num, map);
-        for path in new_paths.iter_mut() {
-            path.push(pos);
-        }
-        paths.append(&mut new_paths);
+        rules.validate().unwrap();
+        assert_eq!(solve(EXAMPLE, &rules), (36, 81));
+    }
+
+    #[test]
+    fn diagonal_and_longer_steps() {
+        let diagonal = HikingRules {
+            neighbourhood: Neighbourhood::Eight,
+            ..HikingRules::default()
+        };
+        assert_eq!(solve("0.\n.1\n", &diagonal), (0, 0));
+        let diagonal = HikingRules { end: 1, ..diagonal };
+        assert_eq!(solve("0.\n.1\n", &diagonal), (1, 1));
+
+        let strides = HikingRules {
+            deltas: vec![1, 2],
+            ..HikingRules::default()
+        };
+        // Climb along the top row in steps of 2 for a while, drop down 1 and
+        // finish along the bottom row, so there's one trail per column.
+        assert_eq!(solve("02468\n13579\n", &strides), (1, 5));
+        assert_eq!(solve("02468\n13579\n", &HikingRules::default()), (0, 0));
+    }
+
+    #[test]
+    fn invalid_rules() {
+        let mixed = HikingRules {
+            deltas: vec![1, -1],
+            ..HikingRules::default()
+        };
+        assert!(mixed.validate().is_err());
+        let backwards = HikingRules {
+            start: 9,
+            end: 0,
+            ..HikingRules::default()
+        };
+        assert!(backwards.validate().is_err());
+        assert!(process_input("0x\n".to_string(), &HikingRules::default()).is_err());
+    }
+
+    #[test]
+    fn non_square_dimensions() {
+        let rules = HikingRules::default();
+        let wide = process_input("0123456789\n1111111111\n".to_string(), &rules).unwrap();
+        assert_eq!((wide.rows(), wide.cols()), (2, 10));
+        assert_eq!(wide.get(&Coordinate::new(0, 9)), Some(&Some(9)));
+        assert_eq!(solve("0123456789\n1111111111\n", &rules), (1, 1));
+
+        let tall: String = (0..10).map(|height| format!("{height}1\n")).collect();
+        let map = process_input(tall.clone(), &rules).unwrap();
+        assert_eq!((map.rows(), map.cols()), (10, 2));
+        assert_eq!(map.get(&Coordinate::new(9, 0)), Some(&Some(9)));
+        assert_eq!(solve(&tall, &rules), (1, 1));
     }
-    // println!("After all directions [{:?}]", paths);
-    paths
 }
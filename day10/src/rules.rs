@@ -0,0 +1,92 @@
+use anyhow::{bail, Result};
+
+use crate::grid::direction::Direction;
+
+/// Which neighbours a trail can step to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Neighbourhood {
+    Four,
+    Eight,
+}
+
+impl Neighbourhood {
+    pub fn directions(&self) -> Vec<Direction> {
+        match self {
+            Neighbourhood::Four => Direction::cardinals().to_vec(),
+            Neighbourhood::Eight => Direction::principles().to_vec(),
+        }
+    }
+}
+
+/// What makes a hiking trail: it begins at height `start`, ends at height
+/// `end`, and each step changes the height by one of `deltas`. Cells marked
+/// with one of `impassable` can't be stepped on at all.
+#[derive(Clone, Debug)]
+pub struct HikingRules {
+    pub start: u8,
+    pub end: u8,
+    pub deltas: Vec<i8>,
+    pub neighbourhood: Neighbourhood,
+    pub impassable: Vec<char>,
+}
+
+impl Default for HikingRules {
+    fn default() -> Self {
+        Self {
+            start: 0,
+            end: 9,
+            deltas: vec![1],
+            neighbourhood: Neighbourhood::Four,
+            impassable: vec!['.'],
+        }
+    }
+}
+
+impl HikingRules {
+    /// Trails are scored by working back from their ends one height at a
+    /// time, which only works if every step moves the same way, towards `end`.
+    pub fn validate(&self) -> Result<()> {
+        if self.start > 9 || self.end > 9 {
+            bail!("Start and end heights must be digits [0-9]");
+        }
+        if self.deltas.is_empty() {
+            bail!("At least one height delta is needed");
+        }
+        if self.deltas.contains(&0) {
+            bail!("Height deltas can't be 0, trails must always climb or descend");
+        }
+        let climbing = self.deltas[0] > 0;
+        if self.deltas.iter().any(|&delta| (delta > 0) != climbing) {
+            bail!("Height deltas must all climb or all descend");
+        }
+        if self.start != self.end && (self.end > self.start) != climbing {
+            bail!(
+                "Trails from {} to {} can't be walked with deltas {:?}",
+                self.start,
+                self.end,
+                self.deltas
+            );
+        }
+        Ok(())
+    }
+
+    pub fn climbing(&self) -> bool {
+        self.deltas[0] > 0
+    }
+
+    /// Whether a trail can step from `from` to `to`.
+    pub fn allows(&self, from: u8, to: u8) -> bool {
+        let delta = to as i16 - from as i16;
+        self.deltas.iter().any(|&allowed| allowed as i16 == delta)
+    }
+
+    /// The heights a trail passes through before reaching its end, nearest
+    /// to the end first.
+    pub fn heights_towards_start(&self) -> Vec<u8> {
+        if self.climbing() {
+            (self.start..self.end).rev().collect()
+        } else {
+            (self.end + 1..=self.start).collect()
+        }
+    }
+}
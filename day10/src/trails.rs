@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use grid::Grid;
+
+use crate::grid::{coordinate::Coordinate, map::Map};
+use crate::rules::HikingRules;
+
+/// A trail map, where `None` marks a cell that can't be walked on.
+pub type TrailMap = Map<Option<u8>>;
+
+/// A set of trail ends, one bit per end index.
+#[derive(Clone, Debug)]
+struct Bitset(Vec<u64>);
+
+impl Bitset {
+    fn new(size: usize) -> Self {
+        Self(vec![0; size.div_ceil(64)])
+    }
+
+    fn insert(&mut self, index: usize) {
+        self.0[index / 64] |= 1 << (index % 64);
+    }
+
+    fn union_with(&mut self, other: &Bitset) {
+        for (word, other) in self.0.iter_mut().zip(&other.0) {
+            *word |= other;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+}
+
+/// The cells of each height, indexed by height.
+fn layers(map: &TrailMap) -> Vec<Vec<Coordinate>> {
+    let mut layers = vec![vec![]; 10];
+    for (coord, height) in map {
+        if let Some(height) = height {
+            layers[*height as usize].push(coord);
+        }
+    }
+    layers
+}
+
+/// Neighbours of `pos` that a trail can step to next.
+fn next_steps<'a>(
+    map: &'a TrailMap,
+    rules: &'a HikingRules,
+    pos: &Coordinate,
+) -> impl Iterator<Item = Coordinate> + 'a {
+    let height = map.get(pos).copied().flatten();
+    let pos = *pos;
+    rules
+        .neighbourhood
+        .directions()
+        .into_iter()
+        .filter_map(move |dir| map.get_relative_cell(&pos, dir))
+        .filter_map(move |(coord, &next)| match (height, next) {
+            (Some(height), Some(next)) if rules.allows(height, next) => Some(coord),
+            _ => None,
+        })
+}
+
+/// The number of distinct trail ends reachable from each trailhead. Works
+/// back from the ends one height at a time, so each cell's set of reachable
+/// ends is the union of its next steps' sets.
+pub fn end_scores(map: &TrailMap, rules: &HikingRules) -> HashMap<Coordinate, usize> {
+    let layers = layers(map);
+    let ends = &layers[rules.end as usize];
+    let mut reachable: HashMap<Coordinate, Bitset> = HashMap::new();
+    for (index, &end) in ends.iter().enumerate() {
+        let mut bitset = Bitset::new(ends.len());
+        bitset.insert(index);
+        reachable.insert(end, bitset);
+    }
+    for height in rules.heights_towards_start() {
+        for pos in &layers[height as usize] {
+            let mut bitset = Bitset::new(ends.len());
+            for next in next_steps(map, rules, pos) {
+                if let Some(onwards) = reachable.get(&next) {
+                    bitset.union_with(onwards);
+                }
+            }
+            reachable.insert(*pos, bitset);
+        }
+    }
+    layers[rules.start as usize]
+        .iter()
+        .map(|pos| (*pos, reachable.get(pos).map_or(0, Bitset::len)))
+        .collect()
+}
+
+/// The number of distinct trails from every cell to a trail end, built the
+/// same way as [`end_scores`] but summing counts instead of sets.
+pub fn path_counts(map: &TrailMap, rules: &HikingRules) -> Map<usize> {
+    let layers = layers(map);
+    let mut counts = Map::new(Grid::new(map.rows(), map.cols()));
+    for &end in &layers[rules.end as usize] {
+        *counts.get_mut(&end).unwrap() = 1;
+    }
+    for height in rules.heights_towards_start() {
+        for pos in &layers[height as usize] {
+            let total = next_steps(map, rules, pos)
+                .map(|next| counts.get(&next).unwrap())
+                .sum();
+            *counts.get_mut(pos).unwrap() = total;
+        }
+    }
+    counts
+}
+
+/// Lazily walks every trail from every trailhead, yielding each as the
+/// cells from trailhead to trail end. Only steps into cells that have at
+/// least one trail onwards, so no time is spent on dead ends.
+pub struct TrailPaths<'a> {
+    map: &'a TrailMap,
+    rules: &'a HikingRules,
+    counts: Map<usize>,
+    stack: Vec<Vec<Coordinate>>,
+}
+
+impl<'a> TrailPaths<'a> {
+    pub fn new(map: &'a TrailMap, rules: &'a HikingRules) -> Self {
+        let counts = path_counts(map, rules);
+        let mut stack: Vec<Vec<Coordinate>> = layers(map)[rules.start as usize]
+            .iter()
+            .filter(|start| counts.get(start) != Some(&0))
+            .map(|&start| vec![start])
+            .collect();
+        stack.reverse();
+        Self {
+            map,
+            rules,
+            counts,
+            stack,
+        }
+    }
+}
+
+impl Iterator for TrailPaths<'_> {
+    type Item = Vec<Coordinate>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(path) = self.stack.pop() {
+            let last = *path.last()?;
+            if self.map.get(&last) == Some(&Some(self.rules.end)) {
+                return Some(path);
+            }
+            let onwards: Vec<Coordinate> = next_steps(self.map, self.rules, &last)
+                .filter(|next| self.counts.get(next) != Some(&0))
+                .collect();
+            for next in onwards.into_iter().rev() {
+                let mut extended = path.clone();
+                extended.push(next);
+                self.stack.push(extended);
+            }
+        }
+        None
+    }
+}
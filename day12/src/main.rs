@@ -1,14 +1,19 @@
 use anyhow::{anyhow, bail, Result};
 use grid::Grid;
 use humantime::format_duration;
-use map::{Direction, Map};
-use std::{collections::VecDeque, fmt::Display, fs::read_to_string, time::Instant};
+use map::Map;
+use regions::Labels;
+use report::RegionReport;
+use std::{env, fs::read_to_string, time::Instant};
 mod map;
+mod regions;
+mod report;
 
 type ProcessedInput = Map<char>;
 type Output = u64;
 
 fn main() -> Result<()> {
+    let args = Args::parse(env::args().skip(1))?;
     let string = read_to_string("input.txt")?;
 
     let time_proc = Instant::now();
@@ -25,14 +30,55 @@ fn main() -> Result<()> {
     let time_one = format_duration(time_one.elapsed());
 
     let time_two = Instant::now();
-    let part_two = solve_part_two(processed);
+    let part_two = solve_part_two(processed.clone());
     let time_two = format_duration(time_two.elapsed());
 
     println!("Part One: {:?} (Took: {})", part_one, time_one);
     println!("Part Two: {:?} (Took: {})", part_two, time_two);
+
+    if let Some(format) = args.report {
+        let reports = region_reports(&processed);
+        match format {
+            ReportFormat::Table => print!("{}", report::table(&reports)),
+            ReportFormat::Csv => print!("{}", report::csv(&reports)),
+        }
+    }
     Ok(())
 }
 
+enum ReportFormat {
+    Table,
+    Csv,
+}
+
+/// Usage: day12 [--report table|csv]
+/// --report prints the area, perimeter, sides and prices of every region.
+struct Args {
+    report: Option<ReportFormat>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Args { report: None };
+        while let Some(flag) = args.next() {
+            let value = args
+                .next()
+                .ok_or(anyhow!("Missing value for argument {flag}"))?;
+            match flag.as_str() {
+                "--report" => {
+                    parsed.report = Some(match value.as_str() {
+                        "table" => ReportFormat::Table,
+                        "csv" => ReportFormat::Csv,
+                        _ => bail!("Unknown report format {value}, expected table or csv"),
+                    })
+                }
+                _ => bail!("Unknown argument {flag}"),
+            }
+        }
+        Ok(parsed)
+    }
+}
+
 fn process_input(string: String) -> Result<ProcessedInput> {
     let lines = string.lines().filter(|e| !e.is_empty());
     let rows = lines.clone().count();
@@ -51,168 +97,88 @@ fn process_input(string: String) -> Result<ProcessedInput> {
 }
 
 fn solve_part_one(map: ProcessedInput) -> Result<Output> {
-    let regions = split_map_into_regions(&map);
-    let mut count = 0;
-    for region in regions {
-        let area = calculate_area(&region);
-        let perimeter = calculate_perimeter(&region);
-        let score = area * perimeter;
-        count += score;
-    }
-    Ok(count)
+    Ok(region_reports(&map).iter().map(RegionReport::price).sum())
 }
 
-fn split_map_into_regions(map: &Map<char>) -> Vec<Map<bool>> {
-    // Create a mutable clone of the map where cells can be consumed.
-    let mut grid: Grid<Option<char>> = Grid::new(map.cells.rows(), map.cells.cols());
-    for (pos, &cell) in map.cells.indexed_iter() {
-        *grid.get_mut(pos.0, pos.1).unwrap() = Some(cell);
-    }
-
-    let mut map_clone = Map::new(grid);
-    let mut regions = vec![];
-
-    // We will manually iterate over the positions without holding a borrow on the map.
-    let positions: Vec<(usize, usize)> = map_clone
-        .cells
-        .indexed_iter()
-        .filter_map(|(pos, &cell)| if cell.is_some() { Some(pos) } else { None })
-        .collect();
-
-    for pos in positions {
-        // Only carve regions for cells that are still present.
-        if map_clone.cells.get(pos.0, pos.1).and_then(|&c| c).is_some() {
-            if let Some(region) = carve_contiguous_region(&mut map_clone, pos) {
-                regions.push(region);
-            }
-        }
-    }
-
-    regions
+fn solve_part_two(map: ProcessedInput) -> Result<Output> {
+    Ok(region_reports(&map)
+        .iter()
+        .map(RegionReport::bulk_price)
+        .sum())
 }
 
-fn carve_contiguous_region(map: &mut Map<Option<char>>, pos: (usize, usize)) -> Option<Map<bool>> {
-    let mut region = Map::new(Grid::new(map.cells.rows(), map.cells.cols()));
-    let cell_type = (*map.cells.get(pos.0, pos.1)?)?; // Clone to avoid borrowing issues
-    let mut positions_to_check = VecDeque::from(vec![pos]);
-
-    while let Some(pos) = positions_to_check.pop_front() {
-        // Clone the cell's content to avoid borrowing issues
-        let cell = match map.cells.get(pos.0, pos.1).cloned() {
-            Some(Some(t)) => t,
-            _ => continue,
-        };
-
-        if cell != cell_type {
-            continue;
-        }
-
-        // We found a new cell. Add it to the region.
-        if let Some(region_cell) = region.cells.get_mut(pos.0, pos.1) {
-            *region_cell = true;
-        }
+/// Measures every region, in the order their top left cells are read.
+fn region_reports(map: &Map<char>) -> Vec<RegionReport> {
+    Labels::new(map).measure()
+}
 
-        // Now remove it from the map.
-        if let Some(map_cell) = map.cells.get_mut(pos.0, pos.1) {
-            *map_cell = None;
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // Add adjacent positions to check.
-        for (adjacent, _) in map.get_cardinal_cells(&pos) {
-            positions_to_check.push_back(adjacent);
-        }
+    fn solve(input: &str) -> (Output, Output) {
+        let map = process_input(input.to_string()).unwrap();
+        (
+            solve_part_one(map.clone()).unwrap(),
+            solve_part_two(map).unwrap(),
+        )
     }
 
-    Some(region)
-}
-
-fn calculate_perimeter(region: &Map<bool>) -> Output {
-    let mut perimeter = 0;
-    for (pos, cell) in region.cells.indexed_iter() {
-        for dir in Direction::cardinals() {
-            let adjacent_cell = region.get_relative_cell(&pos, dir);
-            let is_border = match adjacent_cell {
-                Some((_, &other_cell)) => *cell && !other_cell, // Cell is true AND other is false
-                None => *cell, // Cell is true and adjacent is border of grid
-            };
-            if is_border {
-                perimeter += 1;
-            }
-        }
+    #[test]
+    fn aaaa_bbcd() {
+        let input = include_str!("../fixtures/aaaa_bbcd.txt");
+        assert_eq!(solve(input), (140, 80));
+        let reports = region_reports(&process_input(input.to_string()).unwrap());
+        let measured: Vec<_> = reports
+            .iter()
+            .map(|r| (r.plant, r.area, r.perimeter, r.sides))
+            .collect();
+        assert_eq!(
+            measured,
+            vec![
+                ('A', 4, 10, 4),
+                ('B', 4, 8, 4),
+                ('C', 4, 10, 8),
+                ('D', 1, 4, 4),
+                ('E', 3, 8, 4),
+            ]
+        );
     }
-    perimeter
-}
 
-fn calculate_area(region: &Map<bool>) -> Output {
-    region.cells.iter().filter(|&&e| e).count() as Output
-}
-
-fn solve_part_two(map: ProcessedInput) -> Result<Output> {
-    let regions = split_map_into_regions(&map);
-    let mut count = 0;
-    for region in regions {
-        let area = calculate_area(&region);
-        let sides = calculate_sides(region.clone());
-        let score = area * sides;
-        count += score;
+    #[test]
+    fn e_shaped() {
+        assert_eq!(solve(include_str!("../fixtures/e_shaped.txt")), (692, 236));
     }
-    Ok(count)
-}
 
-fn calculate_sides(mut region: Map<bool>) -> Output {
-    // Given a region, find the number of sides.
-    // We could scan horizontally, check the number of gaps. Then do the same vertically.
-    let mut pad_col = Vec::new();
-    pad_col.resize(region.cells.rows(), false);
-    region.cells.push_col(pad_col);
-    let mut pad_row = Vec::new();
-    pad_row.resize(region.cells.cols(), false);
-    region.cells.push_row(pad_row);
-
-    let mut sides = 0;
-    sides += scan_sides(&region);
-    region.cells.rotate_right(); // Rotate grid clockwise lol
-    sides += scan_sides(&region);
-    sides as Output
-}
-
-fn scan_sides(region: &Map<bool>) -> Output {
-    let mut sides = 0;
-    for (row_num, row_iter) in region.cells.iter_rows().enumerate() {
-        // We start a row. We are looking above in this instance.
-        let mut currently_tracing_side = false;
-        let mut previous_cell = false;
-        for (col_num, &cell) in row_iter.enumerate() {
-            let above_cell = region.get_relative_cell(&(row_num, col_num), Direction::North);
-            let is_edge = match above_cell {
-                Some((_, &t)) => {
-                    // If the above cell is something, then we are tracing a side is the current cell is nothing.
-                    // If the above cell is nothing, then we are tracing a side if the current cell is something.
-                    cell ^ t
-                }
-                None => {
-                    // If the current cell is true, and above us is an edge, then we should definitely be tracking a side.
-                    cell
-                }
-            };
-            // Edge case: Check that the previous cell is the same as this cell.
-            let inverted_edge = previous_cell ^ cell;
-            // If the edge was inverted, we should indicate that we are no longer tracing a side.
-            if inverted_edge && is_edge && currently_tracing_side {
-                sides += 1;
-            }
+    #[test]
+    fn nested_regions() {
+        // Two B regions inside an A region, touching each other diagonally.
+        assert_eq!(solve(include_str!("../fixtures/abba.txt")), (1184, 368));
+        // An A inside a ring of B inside an A.
+        let input = include_str!("../fixtures/rings.txt");
+        assert_eq!(solve(input), (644, 196));
+        let reports = region_reports(&process_input(input.to_string()).unwrap());
+        let sides: Vec<_> = reports.iter().map(|r| (r.plant, r.sides)).collect();
+        assert_eq!(sides, vec![('A', 8), ('B', 8), ('A', 4)]);
+    }
 
-            // We now know if we found an edge.
-            if currently_tracing_side && !is_edge {
-                // We were tracing an edge and it has disappeared.
-                currently_tracing_side = false;
-            } else if !currently_tracing_side && is_edge {
-                //We found a new edge.
-                currently_tracing_side = true;
-                sides += 1;
-            }
-            previous_cell = cell;
-        }
+    #[test]
+    fn report_formats() {
+        let input = include_str!("../fixtures/aaaa_bbcd.txt");
+        let reports = region_reports(&process_input(input.to_string()).unwrap());
+        let csv = report::csv(&reports);
+        assert_eq!(
+            csv.lines().take(2).collect::<Vec<_>>(),
+            vec![
+                "Plant,Area,Perimeter,Sides,Price,Bulk Price",
+                "A,4,10,4,40,16"
+            ]
+        );
+        let table = report::table(&reports);
+        assert_eq!(table.lines().count(), reports.len() + 2);
+        assert_eq!(
+            table.lines().last().unwrap(),
+            "Total |      |           |       |   140 |         80"
+        );
     }
-    sides
 }
@@ -43,19 +43,6 @@ impl Direction {
         ]
     }
 
-    pub fn principles() -> [Direction; 8] {
-        [
-            Direction::North,
-            Direction::East,
-            Direction::South,
-            Direction::West,
-            Direction::NorthEast,
-            Direction::NorthWest,
-            Direction::SouthEast,
-            Direction::SouthWest,
-        ]
-    }
-
     /// Given a Direction, will return a tuple of the direction.
     /// Note that North returns (-1, 0)
     pub fn to_delta(&self) -> (isize, isize) {
@@ -93,7 +80,7 @@ impl<T> Map<T> {
     pub fn get_cardinal_cells(&self, pos: &(usize, usize)) -> Vec<((usize, usize), &T)> {
         let mut cells = vec![];
         for dir in Direction::cardinals() {
-            if let Some(c) = self.get_relative_cell(&pos, dir) {
+            if let Some(c) = self.get_relative_cell(pos, dir) {
                 cells.push(c);
             }
         }
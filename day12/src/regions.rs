@@ -0,0 +1,104 @@
+use std::collections::VecDeque;
+
+use grid::Grid;
+
+use crate::map::{Direction, Map};
+use crate::report::RegionReport;
+
+/// Every cell of the map labelled with the index of its region, along with
+/// the plant growing in each region.
+pub struct Labels {
+    pub cells: Map<usize>,
+    pub plants: Vec<char>,
+}
+
+impl Labels {
+    /// Flood fills the map, numbering regions in the order their first cell
+    /// is read.
+    pub fn new(map: &Map<char>) -> Self {
+        let mut cells: Map<Option<usize>> = Map::new(Grid::new(map.cells.rows(), map.cells.cols()));
+        let mut plants = vec![];
+        for (start, &plant) in map.cells.indexed_iter() {
+            if cells
+                .cells
+                .get(start.0, start.1)
+                .is_some_and(Option::is_some)
+            {
+                continue;
+            }
+            let label = plants.len();
+            plants.push(plant);
+            *cells.cells.get_mut(start.0, start.1).unwrap() = Some(label);
+            let mut to_visit = VecDeque::from([start]);
+            while let Some(pos) = to_visit.pop_front() {
+                for (next, &next_plant) in map.get_cardinal_cells(&pos) {
+                    let next_label = cells.cells.get_mut(next.0, next.1).unwrap();
+                    if next_plant == plant && next_label.is_none() {
+                        *next_label = Some(label);
+                        to_visit.push_back(next);
+                    }
+                }
+            }
+        }
+        let mut labels = Grid::new(map.cells.rows(), map.cells.cols());
+        for (pos, label) in cells.cells.indexed_iter() {
+            *labels.get_mut(pos.0, pos.1).unwrap() = label.unwrap();
+        }
+        Self {
+            cells: Map::new(labels),
+            plants,
+        }
+    }
+
+    fn label(&self, pos: &(usize, usize), direction: Direction) -> Option<usize> {
+        self.cells
+            .get_relative_cell(pos, direction)
+            .map(|(_, &label)| label)
+    }
+
+    /// Measures every region in one sweep over the labels. A region has as
+    /// many sides as corners, and each cell holds a corner of its region in
+    /// each diagonal direction where either both orthogonal neighbours are
+    /// outside the region (convex), or both are inside but the diagonal is
+    /// not (concave).
+    pub fn measure(&self) -> Vec<RegionReport> {
+        let mut reports: Vec<RegionReport> = self
+            .plants
+            .iter()
+            .map(|&plant| RegionReport {
+                plant,
+                area: 0,
+                perimeter: 0,
+                sides: 0,
+            })
+            .collect();
+        for (pos, &label) in self.cells.cells.indexed_iter() {
+            let report = &mut reports[label];
+            report.area += 1;
+            let inside = |direction| self.label(&pos, direction) == Some(label);
+            for direction in Direction::cardinals() {
+                if !inside(direction) {
+                    report.perimeter += 1;
+                }
+            }
+            for (first, second, diagonal) in corners() {
+                match (inside(first), inside(second)) {
+                    (false, false) => report.sides += 1,
+                    (true, true) if !inside(diagonal) => report.sides += 1,
+                    _ => {}
+                }
+            }
+        }
+        reports
+    }
+}
+
+/// The two orthogonal directions either side of each diagonal, and the diagonal.
+fn corners() -> [(Direction, Direction, Direction); 4] {
+    [
+        (Direction::North, Direction::East, Direction::NorthEast),
+        (Direction::East, Direction::South, Direction::SouthEast),
+        (Direction::South, Direction::West, Direction::SouthWest),
+        (Direction::West, Direction::North, Direction::NorthWest),
+    ]
+}
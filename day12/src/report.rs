@@ -0,0 +1,81 @@
+use crate::Output;
+
+/// The measurements of one region, and what fencing it costs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegionReport {
+    pub plant: char,
+    pub area: Output,
+    pub perimeter: Output,
+    pub sides: Output,
+}
+
+impl RegionReport {
+    /// The standard price, paid per unit of fence.
+    pub fn price(&self) -> Output {
+        self.area * self.perimeter
+    }
+
+    /// The bulk discount price, paid per straight side of fence.
+    pub fn bulk_price(&self) -> Output {
+        self.area * self.sides
+    }
+}
+
+const HEADERS: [&str; 6] = ["Plant", "Area", "Perimeter", "Sides", "Price", "Bulk Price"];
+
+fn row(report: &RegionReport) -> [String; 6] {
+    [
+        report.plant.to_string(),
+        report.area.to_string(),
+        report.perimeter.to_string(),
+        report.sides.to_string(),
+        report.price().to_string(),
+        report.bulk_price().to_string(),
+    ]
+}
+
+/// Lays the reports out as a right aligned table, with the totals of both
+/// prices on the last line.
+pub fn table(reports: &[RegionReport]) -> String {
+    let mut rows: Vec<[String; 6]> = vec![HEADERS.map(String::from)];
+    rows.extend(reports.iter().map(row));
+    let total: Output = reports.iter().map(RegionReport::price).sum();
+    let bulk_total: Output = reports.iter().map(RegionReport::bulk_price).sum();
+    rows.push([
+        "Total".to_string(),
+        String::new(),
+        String::new(),
+        String::new(),
+        total.to_string(),
+        bulk_total.to_string(),
+    ]);
+
+    let mut widths = [0; 6];
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.len());
+        }
+    }
+    let mut string = String::new();
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .zip(widths)
+            .map(|(cell, width)| format!("{cell:>width$}"))
+            .collect();
+        string.push_str(&cells.join(" | "));
+        string.push('\n');
+    }
+    string
+}
+
+/// One line per region, with a header line.
+pub fn csv(reports: &[RegionReport]) -> String {
+    let mut string = HEADERS.join(",");
+    string.push('\n');
+    for report in reports {
+        string.push_str(&row(report).join(","));
+        string.push('\n');
+    }
+    string
+}
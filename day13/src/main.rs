@@ -1,25 +1,24 @@
 use anyhow::{anyhow, Result};
 use humantime::format_duration;
-use nom::{character::is_digit, complete::tag, sequence::delimited};
-use serde::Deserialize;
-use std::{fs::read_to_string, time::Instant};
-mod map;
+use parse::parse_games;
+use settings::{Settings, Sweep};
+use std::{env, fs::read_to_string, time::Instant};
+mod parse;
+mod settings;
 
 type ProcessedInput = Vec<Game>;
 type Output = i64;
+type Coordinates = (i64, i64);
 
-#[derive(Clone, Debug, Deserialize)]
+#[derive(Clone, Debug)]
 struct Game {
-    #[serde(rename = "Button A")]
     button_a: Coordinates,
-    #[serde(rename = "Button B")]
     button_b: Coordinates,
-    #[serde(rename = "Prize")]
     prize: Coordinates,
 }
 
 impl Game {
-    fn new(button_a: (i64, i64), button_b: (i64, i64), prize: (i64, i64)) -> Self {
+    fn new(button_a: Coordinates, button_b: Coordinates, prize: Coordinates) -> Self {
         Self {
             button_a,
             button_b,
@@ -27,44 +26,71 @@ impl Game {
         }
     }
 
-    fn solve_part_one(&self, token_costs: (i64, i64)) -> Option<Output> {
-        let (pushes_a, pushes_b) = solve_2d_cramers(self.button_a, self.button_b, self.prize)?;
-        let cost_a = token_costs.0 * pushes_a;
-        let cost_b = token_costs.1 * pushes_b;
-        let total = cost_a + cost_b;
-        Some(total)
-    }
-
-    fn solve_part_two(&self, token_costs: (i64, i64)) -> Option<i64> {
-        // Cramers rule to solve
-        const MEASUREMENT: i64 = 10000000000000;
-        let mut updated_costs = self.clone();
-        updated_costs.prize.0 += MEASUREMENT;
-        updated_costs.prize.1 += MEASUREMENT;
-        updated_costs.solve_part_one(token_costs)
+    /// Returns the cheapest token cost to win this machine once the prize has
+    /// been moved by `prize_offset` along both axes, if it can be won at all.
+    /// Errors if the offset prize or the cost doesn't fit in an i64.
+    fn cost_to_win(&self, token_costs: (i64, i64), prize_offset: i64) -> Result<Option<Output>> {
+        let overflow = || anyhow!("Prize offset {prize_offset} overflows for {self:?}");
+        let prize = (
+            self.prize
+                .0
+                .checked_add(prize_offset)
+                .ok_or_else(overflow)?,
+            self.prize
+                .1
+                .checked_add(prize_offset)
+                .ok_or_else(overflow)?,
+        );
+        let Some((pushes_a, pushes_b)) = solve_2d_cramers(self.button_a, self.button_b, prize)?
+        else {
+            return Ok(None);
+        };
+        if pushes_a < 0 || pushes_b < 0 {
+            return Ok(None);
+        }
+        let cost_a = token_costs.0.checked_mul(pushes_a);
+        let cost_b = token_costs.1.checked_mul(pushes_b);
+        let total = cost_a
+            .zip(cost_b)
+            .and_then(|(a, b)| a.checked_add(b))
+            .ok_or_else(overflow)?;
+        Ok(Some(total))
     }
 }
 
-fn solve_2d_cramers(a: (i64, i64), b: (i64, i64), c: (i64, i64)) -> Option<(i64, i64)> {
+fn solve_2d_cramers(a: (i64, i64), b: (i64, i64), c: (i64, i64)) -> Result<Option<(i64, i64)>> {
     // println!("Cramers: a={:?}, b={:?}, c={:?}", a, b, c);
-    let numerator_x = (c.0 * b.1) - (b.0 * c.1);
-    let denominator_x = (a.0 * b.1) - (b.0 * a.1);
-    let numerator_y = (a.0 * c.1) - (c.0 * a.1);
-    let denominator_y = (a.0 * b.1) - (b.0 * a.1);
+    let cross = |p: (i64, i64), q: (i64, i64)| {
+        p.0.checked_mul(q.1)
+            .zip(q.0.checked_mul(p.1))
+            .and_then(|(l, r)| l.checked_sub(r))
+            .ok_or(anyhow!(
+                "Cramer's rule overflows for a={a:?}, b={b:?}, c={c:?}"
+            ))
+    };
+    let numerator_x = cross(c, b)?;
+    let denominator_x = cross(a, b)?;
+    let numerator_y = cross(a, c)?;
+    let denominator_y = denominator_x;
+    if denominator_x == 0 {
+        // Buttons are collinear, so there's no unique solution.
+        return Ok(None);
+    }
     // println!("X: {numerator_x}/{denominator_y} \t Y:{numerator_y}/{denominator_y}");
     if numerator_x % denominator_x != 0 {
-        return None;
+        return Ok(None);
     }
     if numerator_y % denominator_y != 0 {
-        return None;
+        return Ok(None);
     }
     let x = numerator_x / denominator_x;
     let y = numerator_y / denominator_y;
     // println!("X: {x}, \t Y: {y}");
-    Some((x, y))
+    Ok(Some((x, y)))
 }
 
 fn main() -> Result<()> {
+    let settings = Settings::from_args(env::args().skip(1))?;
     let input = read_to_string("input.txt")?;
     println!("Lines in Input: {}", input.lines().count());
 
@@ -77,8 +103,13 @@ fn main() -> Result<()> {
     );
 
     // println!("{:#?}", processed);
+    if let Some(sweep) = &settings.sweep {
+        print_sweep(&processed, settings.token_costs, sweep);
+        return Ok(());
+    }
+
     let part_one_start = Instant::now();
-    let part_one = solve_part_one(processed.clone());
+    let part_one = solve_part_one(processed.clone(), &settings);
     let time_one = part_one_start.elapsed();
     println!(
         "Part One: {:?} (Took {:?})",
@@ -87,7 +118,7 @@ fn main() -> Result<()> {
     );
 
     let part_two_start = Instant::now();
-    let part_two = solve_part_two(processed);
+    let part_two = solve_part_two(processed, &settings);
     let time_two = part_two_start.elapsed();
     println!(
         "Part Two: {:?} (Took {:?})",
@@ -98,53 +129,56 @@ fn main() -> Result<()> {
 }
 
 fn process_input(input: String) -> Result<ProcessedInput> {
-    let mut games = vec![];
-    let mut game_iter = input.lines();
-    loop {
-        let game = parse_game(&mut game_iter)?;
-        match game {
-            Some(t) => games.push(t),
-            None => return Ok(games),
-        }
-        let _ = game_iter.next();
-    }
+    parse_games(&input)
 }
 
-fn parse_game(game_iter: &mut std::str::Lines<'_>) -> Result<Option<Game>> {
-    let mut input = String::new();
-    for _ in 0..3 {
-        input.push_str(game_iter.next().ok_or(anyhow!("Game input not finished"))?);
-    }
-    let formatted_input = input
-        .replace(": X+", ": {\"X\": ")
-        .replace(", Y+", ", \"Y\": ")
-        .replace(": X=", ": {\"X\": ")
-        .replace(", Y=", ", \"Y\": ")
-        .replace("\n", "}, \n")
-        + "}";
-
-    match serde_json::from_str::<Game>(&formatted_input) {
-        Ok(game) => println!("Parsed successfully: {:#?}", game),
-        Err(err) => eprintln!("Failed to parse input: {:#?}", err),
-    }
+fn solve_part_one(data: ProcessedInput, settings: &Settings) -> Result<Output> {
+    total_cost(&data, settings.token_costs, 0)
+}
+
+fn solve_part_two(data: ProcessedInput, settings: &Settings) -> Result<Output> {
+    total_cost(&data, settings.token_costs, settings.prize_offset)
 }
 
-fn solve_part_one(data: ProcessedInput) -> Result<Output> {
-    let mut total_tokens = 0;
+/// Sums the cost of every machine that can be won.
+fn total_cost(data: &ProcessedInput, token_costs: (i64, i64), prize_offset: i64) -> Result<Output> {
+    let mut total_tokens: Output = 0;
     for game in data {
-        if let Some(tokens) = game.solve_part_one((3, 1)) {
-            total_tokens += tokens;
+        if let Some(tokens) = game.cost_to_win(token_costs, prize_offset)? {
+            total_tokens = total_tokens.checked_add(tokens).ok_or(anyhow!(
+                "Total cost overflows at prize offset {prize_offset}"
+            ))?;
         }
     }
     Ok(total_tokens)
 }
 
-fn solve_part_two(data: ProcessedInput) -> Result<Output> {
-    let mut total_tokens = 0;
-    for game in data {
-        if let Some(tokens) = game.solve_part_two((3, 1)) {
-            total_tokens += tokens;
+/// Prints how many machines can be won, and what winning all of them costs,
+/// for every prize offset in the sweep. Offsets that overflow are reported
+/// rather than summed.
+fn print_sweep(data: &ProcessedInput, token_costs: (i64, i64), sweep: &Sweep) {
+    println!("{:>20} {:>10} {:>20}", "Offset", "Winnable", "Total Cost");
+    for offset in sweep.offsets() {
+        let winnable: Result<usize> = data.iter().try_fold(0, |count, game| {
+            Ok(count + game.cost_to_win(token_costs, offset)?.is_some() as usize)
+        });
+        match (winnable, total_cost(data, token_costs, offset)) {
+            (Ok(winnable), Ok(total)) => println!("{offset:>20} {winnable:>10} {total:>20}"),
+            _ => println!("{offset:>20} {:>10} {:>20}", "-", "overflow"),
         }
     }
-    Ok(total_tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cost_to_win_checks_for_overflow() {
+        let game = Game::new((94, 34), (22, 67), (8400, 5400));
+        assert_eq!(game.cost_to_win((3, 1), 0).unwrap(), Some(280));
+        assert!(game.cost_to_win((3, 1), i64::MAX).is_err());
+        assert!(game.cost_to_win((i64::MAX, 1), 0).is_err());
+        assert!(total_cost(&vec![game.clone(), game], (i64::MAX / 100, 1), 0).is_err());
+    }
 }
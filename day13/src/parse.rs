@@ -0,0 +1,127 @@
+use anyhow::{anyhow, Result};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{i64, line_ending, multispace0, space0},
+    combinator::{all_consuming, cut},
+    error::{context, convert_error, VerboseError},
+    multi::many0,
+    sequence::{delimited, preceded, separated_pair, terminated, tuple},
+    IResult,
+};
+
+use crate::{Coordinates, Game};
+
+type ParseResult<'a, T> = IResult<&'a str, T, VerboseError<&'a str>>;
+
+/// Parses every claw machine block in the input.
+/// Blocks may be separated by any number of blank lines, and parse failures
+/// are reported with the line and column of the offending text.
+pub fn parse_games(input: &str) -> Result<Vec<Game>> {
+    let mut parser = all_consuming(terminated(many0(preceded(multispace0, game)), multispace0));
+    match parser(input) {
+        Ok((_, games)) => Ok(games),
+        Err(nom::Err::Error(e)) | Err(nom::Err::Failure(e)) => Err(anyhow!(
+            "Failed to parse input:\n{}",
+            convert_error(input, e)
+        )),
+        Err(nom::Err::Incomplete(_)) => Err(anyhow!("Input ended unexpectedly")),
+    }
+}
+
+/// Button A: X+94, Y+34
+/// Button B: X+22, Y+67
+/// Prize: X=8400, Y=5400
+fn game(input: &str) -> ParseResult<'_, Game> {
+    // Once a block has started, any error inside it is fatal so the error
+    // points at the bad line rather than the start of the block.
+    let (input, _) = tag("Button A:")(input)?;
+    let (input, (button_a, button_b, prize)) = cut(context(
+        "claw machine",
+        tuple((
+            terminated(button_offsets, end_of_line),
+            delimited(tag("Button B:"), button_offsets, end_of_line),
+            preceded(tag("Prize:"), prize_location),
+        )),
+    ))(input)?;
+    Ok((input, Game::new(button_a, button_b, prize)))
+}
+
+fn button_offsets(input: &str) -> ParseResult<'_, Coordinates> {
+    context("button offsets", coordinates("X+", "Y+"))(input)
+}
+
+fn prize_location(input: &str) -> ParseResult<'_, Coordinates> {
+    context("prize location", coordinates("X=", "Y="))(input)
+}
+
+fn coordinates<'a>(
+    x_prefix: &'static str,
+    y_prefix: &'static str,
+) -> impl FnMut(&'a str) -> ParseResult<'a, Coordinates> {
+    preceded(
+        space0,
+        separated_pair(
+            preceded(tag(x_prefix), i64),
+            tuple((space0, tag(","), space0)),
+            preceded(tag(y_prefix), i64),
+        ),
+    )
+}
+
+fn end_of_line(input: &str) -> ParseResult<'_, &str> {
+    preceded(space0, line_ending)(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+";
+
+    fn fields(games: &[Game]) -> Vec<(Coordinates, Coordinates, Coordinates)> {
+        games
+            .iter()
+            .map(|g| (g.button_a, g.button_b, g.prize))
+            .collect()
+    }
+
+    #[test]
+    fn parses_example_blocks() {
+        let games = parse_games(EXAMPLE).unwrap();
+        assert_eq!(
+            fields(&games),
+            vec![
+                ((94, 34), (22, 67), (8400, 5400)),
+                ((26, 66), (67, 21), (12748, 12176)),
+            ]
+        );
+    }
+
+    #[test]
+    fn tolerates_crlf_and_blank_lines() {
+        let expected = fields(&parse_games(EXAMPLE).unwrap());
+        let crlf = EXAMPLE.replace('\n', "\r\n");
+        assert_eq!(fields(&parse_games(&crlf).unwrap()), expected);
+
+        let spaced = format!("\n\n{}", EXAMPLE.replace("\n\n", "\n\n\n\n")) + "\n\n";
+        assert_eq!(fields(&parse_games(&spaced).unwrap()), expected);
+
+        // No trailing newline after the last prize.
+        assert_eq!(fields(&parse_games(EXAMPLE.trim_end()).unwrap()), expected);
+    }
+
+    #[test]
+    fn reports_the_malformed_line() {
+        let broken = EXAMPLE.replace("Button B: X+67, Y+21", "Button B: X+67; Y+21");
+        let error = parse_games(&broken).unwrap_err().to_string();
+        assert!(error.contains("at line 6"), "{error}");
+        assert!(error.contains("Button B: X+67; Y+21"), "{error}");
+    }
+}
@@ -0,0 +1,133 @@
+use anyhow::{anyhow, bail, Context, Result};
+
+/// Runtime knobs for the claw machines.
+/// Usage: day13 [--costs A,B] [--offset N] [--sweep START..END[:STEP]]
+#[derive(Clone, Debug)]
+pub struct Settings {
+    pub token_costs: (i64, i64),
+    pub prize_offset: i64,
+    pub sweep: Option<Sweep>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Sweep {
+    pub start: i64,
+    pub end: i64,
+    pub step: i64,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            token_costs: (3, 1),
+            prize_offset: 10000000000000,
+            sweep: None,
+        }
+    }
+}
+
+impl Settings {
+    pub fn from_args(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut settings = Settings::default();
+        while let Some(flag) = args.next() {
+            let value = args
+                .next()
+                .ok_or(anyhow!("Missing value for argument {flag}"))?;
+            match flag.as_str() {
+                "--costs" => settings.token_costs = parse_pair(&value)?,
+                "--offset" => settings.prize_offset = parse_number(&value)?,
+                "--sweep" => settings.sweep = Some(Sweep::parse(&value)?),
+                _ => bail!("Unknown argument {flag}"),
+            }
+        }
+        Ok(settings)
+    }
+}
+
+impl Sweep {
+    /// Parses "START..END" or "START..END:STEP". END is inclusive.
+    fn parse(value: &str) -> Result<Self> {
+        let (range, step) = match value.split_once(':') {
+            Some((range, step)) => (range, parse_number(step)?),
+            None => (value, 1),
+        };
+        let (start, end) = range.split_once("..").ok_or(anyhow!(
+            "Sweep must look like START..END[:STEP], got {value}"
+        ))?;
+        let sweep = Sweep {
+            start: parse_number(start)?,
+            end: parse_number(end)?,
+            step,
+        };
+        if sweep.step <= 0 {
+            bail!("Sweep step must be positive, got {}", sweep.step);
+        }
+        if sweep.start > sweep.end {
+            bail!("Sweep start {} is after end {}", sweep.start, sweep.end);
+        }
+        Ok(sweep)
+    }
+
+    pub fn offsets(&self) -> impl Iterator<Item = i64> {
+        (self.start..=self.end).step_by(self.step as usize)
+    }
+}
+
+fn parse_pair(value: &str) -> Result<(i64, i64)> {
+    let (a, b) = value
+        .split_once(',')
+        .ok_or(anyhow!("Expected a pair like A,B, got {value}"))?;
+    Ok((parse_number(a)?, parse_number(b)?))
+}
+
+fn parse_number(value: &str) -> Result<i64> {
+    value
+        .trim()
+        .parse()
+        .with_context(|| format!("Failed to parse number {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &str) -> impl Iterator<Item = String> + '_ {
+        args.split_whitespace().map(String::from)
+    }
+
+    #[test]
+    fn reads_arguments() {
+        let defaults = Settings::from_args(args("")).unwrap();
+        assert_eq!(defaults.token_costs, (3, 1));
+        assert_eq!(defaults.prize_offset, 10000000000000);
+        assert!(defaults.sweep.is_none());
+
+        let settings =
+            Settings::from_args(args("--costs 5,2 --offset -7 --sweep 0..10:5")).unwrap();
+        assert_eq!(settings.token_costs, (5, 2));
+        assert_eq!(settings.prize_offset, -7);
+        let offsets: Vec<i64> = settings.sweep.unwrap().offsets().collect();
+        assert_eq!(offsets, vec![0, 5, 10]);
+
+        assert!(Settings::from_args(args("--costs 5")).is_err());
+        assert!(Settings::from_args(args("--offset")).is_err());
+        assert!(Settings::from_args(args("--offset many")).is_err());
+        assert!(Settings::from_args(args("--speed 2")).is_err());
+    }
+
+    #[test]
+    fn parses_sweeps() {
+        let sweep = Sweep::parse("3..6").unwrap();
+        assert_eq!((sweep.start, sweep.end, sweep.step), (3, 6, 1));
+        assert_eq!(sweep.offsets().collect::<Vec<_>>(), vec![3, 4, 5, 6]);
+
+        let near_max = format!("{}..{}:2", i64::MAX - 3, i64::MAX);
+        let offsets: Vec<i64> = Sweep::parse(&near_max).unwrap().offsets().collect();
+        assert_eq!(offsets, vec![i64::MAX - 3, i64::MAX - 1]);
+
+        assert!(Sweep::parse("6..3").is_err());
+        assert!(Sweep::parse("0..3:0").is_err());
+        assert!(Sweep::parse("0..3:-1").is_err());
+        assert!(Sweep::parse("0-3").is_err());
+    }
+}
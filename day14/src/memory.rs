@@ -0,0 +1,170 @@
+// Heap accounting per phase, built with `--features memory`. Without the
+// feature the counting allocator isn't installed and phases report nothing.
+// Like the grid module it's copied into each day that wants it: day06 and
+// day14, whose solvers clone whole maps, and the dayXX template for new days.
+#![cfg_attr(not(feature = "memory"), allow(dead_code))]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    fmt::Display,
+    sync::atomic::{AtomicUsize, Ordering::Relaxed},
+};
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static TOTAL: AtomicUsize = AtomicUsize::new(0);
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Hands everything to the system allocator, keeping count as it goes.
+pub struct Counting;
+
+fn grew(size: usize) {
+    let current = CURRENT.fetch_add(size, Relaxed) + size;
+    PEAK.fetch_max(current, Relaxed);
+    TOTAL.fetch_add(size, Relaxed);
+    COUNT.fetch_add(1, Relaxed);
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            grew(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Relaxed);
+    }
+
+    // A realloc counts as a fresh allocation of the new size, since that's
+    // what it costs when it can't grow in place.
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new = System.realloc(ptr, layout, new_size);
+        if !new.is_null() {
+            CURRENT.fetch_sub(layout.size(), Relaxed);
+            grew(new_size);
+        }
+        new
+    }
+}
+
+/// What the heap did while a phase ran.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Usage {
+    /// The most that was live at once, including whatever was already live
+    /// when the phase started.
+    pub peak: usize,
+    pub total: usize,
+    pub allocations: usize,
+}
+
+impl Display for Usage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Heap: peak {}, allocated {} in {} allocation{}",
+            bytes(self.peak),
+            bytes(self.total),
+            self.allocations,
+            if self.allocations == 1 { "" } else { "s" }
+        )
+    }
+}
+
+fn bytes(bytes: usize) -> String {
+    match bytes {
+        0..1024 => format!("{bytes}B"),
+        1024..1048576 => format!("{:.1}KiB", bytes as f64 / 1024.),
+        1048576..1073741824 => format!("{:.1}MiB", bytes as f64 / 1048576.),
+        _ => format!("{:.1}GiB", bytes as f64 / 1073741824.),
+    }
+}
+
+/// Marks the start of a phase. Phases are expected to run one at a time.
+pub struct Phase {
+    total: usize,
+    allocations: usize,
+}
+
+impl Phase {
+    pub fn start() -> Self {
+        PEAK.store(CURRENT.load(Relaxed), Relaxed);
+        Self {
+            total: TOTAL.load(Relaxed),
+            allocations: COUNT.load(Relaxed),
+        }
+    }
+
+    /// The usage since `start`, or None when the allocator isn't counting.
+    pub fn finish(self) -> Option<Usage> {
+        if !cfg!(feature = "memory") {
+            return None;
+        }
+        Some(Usage {
+            peak: PEAK.load(Relaxed),
+            total: TOTAL.load(Relaxed) - self.total,
+            allocations: COUNT.load(Relaxed) - self.allocations,
+        })
+    }
+}
+
+/// Prints the usage under the timing line it belongs to, if there is any.
+pub fn report(usage: Option<Usage>) {
+    if let Some(usage) = usage {
+        println!("    {usage}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "memory")]
+    #[test]
+    fn counts_a_phase() {
+        let phase = Phase::start();
+        let mut buffers = vec![];
+        for _ in 0..4 {
+            buffers.push(vec![0u8; 1 << 20]);
+        }
+        drop(buffers);
+        let usage = phase.finish().unwrap();
+        // Other tests may be allocating at the same time, so only lower bounds hold.
+        assert!(usage.peak >= 4 << 20);
+        assert!(usage.total >= 4 << 20);
+        assert!(usage.allocations >= 5);
+    }
+
+    #[test]
+    fn formats_sizes() {
+        let usage = Usage {
+            peak: 3 << 20,
+            total: 1536,
+            allocations: 2,
+        };
+        assert_eq!(
+            usage.to_string(),
+            "Heap: peak 3.0MiB, allocated 1.5KiB in 2 allocations"
+        );
+        let usage = Usage {
+            peak: 200,
+            total: 5 << 30,
+            allocations: 1,
+        };
+        assert_eq!(
+            usage.to_string(),
+            "Heap: peak 200B, allocated 5.0GiB in 1 allocation"
+        );
+    }
+}
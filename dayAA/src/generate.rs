@@ -0,0 +1,66 @@
+use anyhow::{bail, Result};
+use grid::Grid;
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+
+use crate::grid::{coordinate::Coordinate, map::Map};
+use crate::solve::{SCell, Walk};
+
+/// How many fresh starts to try before giving up on a size.
+const ATTEMPTS: usize = 100;
+
+/// Generates a `rows` by `cols` map with exactly one walk that fills it.
+///
+/// Starts from an open map with the player somewhere random, then puts up
+/// walls in random cells one at a time. A wall that leaves no way to fill
+/// the map is taken down again, and the map is done as soon as the solver
+/// finds only one way to fill it.
+///
+/// Proving a wall leaves no fill at all is the slow part, and gets much
+/// slower with size: 7x7 takes around a second, 8x8 a few minutes.
+pub fn generate(rows: usize, cols: usize, seed: u64) -> Result<Map<SCell>> {
+    if rows * cols < 2 {
+        bail!("Map must have room for the player and at least one empty cell");
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    for _ in 0..ATTEMPTS {
+        let mut map = Map::new(Grid::init(rows, cols, SCell::Empty(false)));
+        let player = Coordinate::new(rng.gen_range(0..rows), rng.gen_range(0..cols));
+        *map.get_mut(&player).unwrap() = SCell::Player;
+
+        let mut candidates: Vec<Coordinate> = (&map)
+            .into_iter()
+            .map(|(pos, _)| pos)
+            .filter(|&pos| pos != player)
+            .collect();
+        candidates.shuffle(&mut rng);
+        if Walk::count_fills(&map, 2)? == 1 {
+            return Ok(map);
+        }
+        for pos in candidates {
+            *map.get_mut(&pos).unwrap() = SCell::Wall;
+            match Walk::count_fills(&map, 2)? {
+                0 => *map.get_mut(&pos).unwrap() = SCell::Empty(false),
+                1 => return Ok(map),
+                _ => {}
+            }
+        }
+    }
+    bail!("Failed to generate a {rows}x{cols} map with a unique fill after {ATTEMPTS} attempts")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solve::process_input;
+
+    #[test]
+    fn generated_maps_have_one_fill() {
+        for seed in 0..5 {
+            let map = generate(4, 5, seed).unwrap();
+            let parsed = process_input(map.to_string()).unwrap();
+            assert_eq!((parsed.cells.rows(), parsed.cols()), (4, 5));
+            assert_eq!(Walk::count_fills(&parsed, 10).unwrap(), 1);
+            assert!(Walk::find(&parsed).unwrap().is_some());
+        }
+    }
+}
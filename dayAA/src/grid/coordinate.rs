@@ -1,3 +1,4 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Coordinate {
     pub row: usize,
     pub col: usize,
@@ -13,10 +14,4 @@ impl Coordinate {
         let col = self.col.checked_add_signed(delta_col)?;
         Some(Coordinate::new(row, col))
     }
-
-    pub fn checked_add(&self, delta_row: usize, delta_col: usize) -> Option<Coordinate> {
-        let row = self.row.checked_add(delta_row)?;
-        let col = self.col.checked_add(delta_col)?;
-        Some(Coordinate::new(row, col))
-    }
 }
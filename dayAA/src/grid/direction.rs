@@ -1,12 +1,9 @@
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Direction {
     North,
     South,
     East,
     West,
-    NorthEast,
-    NorthWest,
-    SouthEast,
-    SouthWest,
 }
 
 impl Direction {
@@ -19,19 +16,6 @@ impl Direction {
         ]
     }
 
-    pub fn principles() -> [Direction; 8] {
-        [
-            Direction::North,
-            Direction::East,
-            Direction::South,
-            Direction::West,
-            Direction::NorthEast,
-            Direction::NorthWest,
-            Direction::SouthEast,
-            Direction::SouthWest,
-        ]
-    }
-
     /// Given a Direction, will return a tuple of the direction.
     /// Note that North returns (-1, 0)
     pub fn to_delta(&self) -> (isize, isize) {
@@ -40,10 +24,6 @@ impl Direction {
             Direction::South => (1, 0),
             Direction::East => (0, 1),
             Direction::West => (0, -1),
-            Direction::NorthEast => (-1, 1),
-            Direction::NorthWest => (-1, -1),
-            Direction::SouthEast => (1, 1),
-            Direction::SouthWest => (1, -1),
         }
     }
 }
@@ -1,11 +1,8 @@
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, Result};
 use grid::Grid;
 use std::fmt::Display;
 
-use super::{
-    coordinate::{self, Coordinate},
-    direction::Direction,
-};
+use super::{coordinate::Coordinate, direction::Direction};
 
 #[derive(Clone, Debug)]
 pub struct Map<T> {
@@ -43,16 +40,6 @@ impl<T> Map<T> {
         Some((new_pos, cell))
     }
 
-    pub fn get_cardinal_cells(&self, pos: &Coordinate) -> Vec<(Coordinate, &T)> {
-        let mut cells = vec![];
-        for dir in Direction::cardinals() {
-            if let Some(c) = self.get_relative_cell(pos, dir) {
-                cells.push(c);
-            }
-        }
-        cells
-    }
-
     pub fn get(&self, coordinate: &Coordinate) -> Option<&T> {
         self.cells.get(coordinate.row, coordinate.col)
     }
@@ -61,20 +48,12 @@ impl<T> Map<T> {
         self.cells.get_mut(coordinate.row, coordinate.col)
     }
 
-    pub fn rows(&self) -> usize {
-        self.cells.rows()
-    }
     pub fn cols(&self) -> usize {
         self.cells.cols()
     }
 }
 
 impl<T: Default> Map<T> {
-    pub fn clone_size(&self) -> Map<T> {
-        let grid = Grid::new(self.rows(), self.cols());
-        Map::new(grid)
-    }
-
     pub fn from_str_with_coords(
         input: &str,
         cell_fn: impl Fn(char, Coordinate) -> Result<T>,
@@ -1,12 +1,22 @@
-use anyhow::Result;
+use anyhow::{anyhow, bail, Result};
+use generate::generate;
 use humantime::format_duration;
-use solve::{process_input, solve_part_one, solve_part_two};
-use std::{fs::read_to_string, time::Instant};
+use solve::{process_input, solve_part_one, solve_part_two, Longest, Walk};
+use std::{env, fs::read_to_string, time::Instant};
+mod generate;
 mod grid;
 mod solve;
 
 fn main() -> Result<()> {
-    let input = read_to_string("input.txt")?;
+    let args = Args::parse(env::args().skip(1))?;
+    let input = match args.generate {
+        Some((rows, cols)) => {
+            let map = generate(rows, cols, args.seed)?.to_string();
+            println!("{map}");
+            map
+        }
+        None => read_to_string("input.txt")?,
+    };
     println!("Lines in Input: {}", input.lines().count());
 
     let processing_start = Instant::now();
@@ -16,7 +26,6 @@ fn main() -> Result<()> {
         "Processing Complete. (Took {:?})",
         format_duration(time_processing).to_string()
     );
-    // println!("{}", string_map(&processed));
     let part_one_start = Instant::now();
     let part_one = solve_part_one(processed.clone());
     let time_one = part_one_start.elapsed();
@@ -27,12 +36,65 @@ fn main() -> Result<()> {
     );
 
     let part_two_start = Instant::now();
-    let part_two = solve_part_two(processed);
+    let part_two = solve_part_two(processed.clone());
     let time_two = part_two_start.elapsed();
     println!(
         "Part Two: {:?} (Took {:?})",
         part_two,
         format_duration(time_two).to_string()
     );
+
+    if args.show {
+        let (kind, walk) = match Walk::find(&processed)? {
+            Some(walk) => ("Complete", walk),
+            None => match Walk::longest(&processed)? {
+                Longest::Proven(walk) => ("Longest", walk),
+                Longest::BestFound(walk) => ("Longest found", walk),
+            },
+        };
+        let moves: Vec<String> = walk.directions.iter().map(|d| format!("{d:?}")).collect();
+        println!("{kind} walk ({} moves): {}", moves.len(), moves.join(", "));
+        println!("{}", walk.fill(&processed));
+    }
     Ok(())
 }
+
+/// Usage: SlideMaze [--show] [--generate ROWSxCOLS [--seed SEED]]
+/// --show prints the walk that fills the map, or failing that the longest
+/// walk that could be found, and the map it fills.
+/// --generate swaps input.txt for a random map with exactly one fill.
+struct Args {
+    show: bool,
+    generate: Option<(usize, usize)>,
+    seed: u64,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self> {
+        let mut parsed = Args {
+            show: false,
+            generate: None,
+            seed: 0,
+        };
+        while let Some(flag) = args.next() {
+            if flag == "--show" {
+                parsed.show = true;
+                continue;
+            }
+            let value = args
+                .next()
+                .ok_or(anyhow!("Missing value for argument {flag}"))?;
+            match flag.as_str() {
+                "--generate" => {
+                    let (rows, cols) = value
+                        .split_once('x')
+                        .ok_or(anyhow!("Size must look like ROWSxCOLS, got {value}"))?;
+                    parsed.generate = Some((rows.parse()?, cols.parse()?));
+                }
+                "--seed" => parsed.seed = value.parse()?,
+                _ => bail!("Unknown argument {flag}"),
+            }
+        }
+        Ok(parsed)
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::fmt::Display;
 use std::fmt::Write;
 
@@ -5,11 +6,11 @@ use anyhow::anyhow;
 use anyhow::bail;
 use anyhow::Result;
 
-use crate::grid::map::Map;
+use crate::grid::{coordinate::Coordinate, direction::Direction, map::Map};
 pub type ProcessedInput = Map<SCell>;
 
 type Visited = bool;
-#[derive(Clone, Default)]
+#[derive(Clone, Default, PartialEq, Eq)]
 pub enum SCell {
     Empty(Visited),
     #[default]
@@ -49,34 +50,356 @@ fn str_to_cell(c: char) -> Result<SCell> {
 }
 
 type NumMoves = u32;
-pub fn solve_part_one(mut data: ProcessedInput) -> Result<NumMoves> {
-    println!("{}", data);
-    let end_states = get_next_possibilities(data);
-    end_states
-        .iter()
-        .filter(|(m, _)| is_completely_filled(m))
-        .map(|(_, a)| a)
-        .max()
-        .ok_or(anyhow!("No solutions possible"))
-        .copied()
+pub fn solve_part_one(data: ProcessedInput) -> Result<NumMoves> {
+    match Walk::find(&data)? {
+        Some(walk) => Ok(walk.directions.len() as NumMoves),
+        None => bail!("No solutions possible, no walk visits every empty cell"),
+    }
 }
 
-fn is_completely_filled(m: &Map<SCell>) -> bool {
-    for (_, cell) in m {
-        let valid = matches!(cell, SCell::Empty(true) | SCell::Player);
-        if !valid {
-            return false;
+/// A walk of single steps from the player's starting cell, never stepping
+/// onto a wall or a cell it has already visited.
+#[derive(Clone, Debug)]
+pub struct Walk {
+    pub start: Coordinate,
+    pub directions: Vec<Direction>,
+}
+
+/// The longest walk, or the longest found before the search gave up.
+#[derive(Clone, Debug)]
+pub enum Longest {
+    Proven(Walk),
+    BestFound(Walk),
+}
+
+/// How many steps the longest walk search takes before settling for the
+/// best walk it has found.
+const STEP_LIMIT: usize = 2_000_000;
+
+impl Walk {
+    /// Searches for a walk that fills the map.
+    pub fn find(map: &Map<SCell>) -> Result<Option<Walk>> {
+        let mut search = Search::new(map)?;
+        let remaining = search.remaining();
+        search.fill(search.start, remaining, 1);
+        Ok(search.filled.take().map(|steps| search.walk(&steps)))
+    }
+
+    /// Searches for the longest walk, for when no walk fills the map. Only
+    /// walks that could still beat the best so far are followed, but on
+    /// large open maps that can still be too many, so after `STEP_LIMIT`
+    /// steps it settles for the best found.
+    pub fn longest(map: &Map<SCell>) -> Result<Longest> {
+        let mut search = Search::new(map)?;
+        let mut steps = STEP_LIMIT;
+        search.longest(search.start, &mut steps);
+        let walk = search.walk(&search.longest);
+        Ok(if steps > 0 {
+            Longest::Proven(walk)
+        } else {
+            Longest::BestFound(walk)
+        })
+    }
+
+    /// Counts the walks that fill the map, stopping once `limit` are found.
+    pub fn count_fills(map: &Map<SCell>, limit: usize) -> Result<usize> {
+        let mut search = Search::new(map)?;
+        let remaining = search.remaining();
+        Ok(search.fill(search.start, remaining, limit))
+    }
+
+    /// The map after the walk, with every visited cell filled in and the
+    /// player where the walk ends.
+    pub fn fill(&self, map: &Map<SCell>) -> Map<SCell> {
+        let mut filled = map.clone();
+        let mut pos = self.start;
+        for direction in &self.directions {
+            *filled.get_mut(&pos).unwrap() = SCell::Empty(true);
+            let (next, _) = filled
+                .get_relative_cell(&pos, direction.clone())
+                .expect("Walks stay on the map");
+            pos = next;
         }
+        *filled.get_mut(&pos).unwrap() = SCell::Player;
+        filled
     }
-    true
 }
 
-fn get_next_possibilities(data: Map<SCell>) -> Vec<(Map<SCell>, NumMoves)> {
-    //Given a map, make valid moves.
-    let player_coord = 
+/// Backtracking search over the map flattened into cell indices. Each
+/// cell's neighbours are kept with the index of their direction in
+/// `Direction::cardinals()`.
+struct Search {
+    cols: usize,
+    start: usize,
+    free: Vec<bool>,
+    neighbours: Vec<Vec<(usize, usize)>>,
+    path: Vec<usize>,
+    filled: Option<Vec<usize>>,
+    longest: Vec<usize>,
+}
+
+impl Search {
+    fn new(map: &Map<SCell>) -> Result<Self> {
+        let cols = map.cols();
+        let mut start = None;
+        let mut free = vec![];
+        let mut neighbours = vec![];
+        for (pos, cell) in map {
+            if *cell == SCell::Player {
+                if start.is_some() {
+                    bail!("Map must contain exactly one player, found a second at {pos:?}");
+                }
+                start = Some(pos.row * cols + pos.col);
+            }
+            free.push(*cell == SCell::Empty(false));
+            let open = Direction::cardinals()
+                .into_iter()
+                .enumerate()
+                .filter_map(|(step, dir)| {
+                    let (next, cell) = map.get_relative_cell(&pos, dir)?;
+                    (*cell != SCell::Wall).then_some((next.row * cols + next.col, step))
+                })
+                .collect();
+            neighbours.push(open);
+        }
+        Ok(Self {
+            cols,
+            start: start.ok_or(anyhow!("Map must contain a player"))?,
+            free,
+            neighbours,
+            path: vec![],
+            filled: None,
+            longest: vec![],
+        })
+    }
+
+    fn coordinate(&self, index: usize) -> Coordinate {
+        Coordinate::new(index / self.cols, index % self.cols)
+    }
+
+    fn remaining(&self) -> usize {
+        self.free.iter().filter(|&&free| free).count()
+    }
+
+    /// Counts the ways of visiting all `remaining` free cells starting from
+    /// `pos`, giving up once `limit` have been found. The first one found is
+    /// kept in `self.filled`.
+    fn fill(&mut self, pos: usize, remaining: usize, limit: usize) -> usize {
+        if remaining == 0 {
+            if self.filled.is_none() {
+                self.filled = Some(self.path.clone());
+            }
+            return 1;
+        }
+        if !self.can_still_fill(pos, remaining) {
+            return 0;
+        }
+
+        // Warnsdorff's rule: cells with the fewest ways on are the easiest to
+        // strand, so visit them first.
+        let mut moves: Vec<(usize, usize)> = self.neighbours[pos]
+            .iter()
+            .filter(|(next, _)| self.free[*next])
+            .copied()
+            .collect();
+        moves.sort_by_key(|&(next, _)| self.free_degree(next));
+        let mut found = 0;
+        for (next, step) in moves {
+            self.free[next] = false;
+            self.path.push(step);
+            found += self.fill(next, remaining - 1, limit - found);
+            self.path.pop();
+            self.free[next] = true;
+            if found >= limit {
+                break;
+            }
+        }
+        found
+    }
+
+    fn walk(&self, steps: &[usize]) -> Walk {
+        Walk {
+            start: self.coordinate(self.start),
+            directions: steps
+                .iter()
+                .map(|&step| Direction::cardinals()[step].clone())
+                .collect(),
+        }
+    }
+
+    /// Tries the walks from `pos` that could still be longer than
+    /// `self.longest`, keeping the longest. Each call uses up one of
+    /// `steps`, and the search stops when they run out.
+    fn longest(&mut self, pos: usize, steps: &mut usize) {
+        if self.path.len() > self.longest.len() {
+            self.longest = self.path.clone();
+        }
+        if *steps == 0 || self.path.len() + self.reachable_bound(pos) <= self.longest.len() {
+            return;
+        }
+        *steps -= 1;
+        for (next, step) in self.neighbours[pos].clone() {
+            if !self.free[next] {
+                continue;
+            }
+            self.free[next] = false;
+            self.path.push(step);
+            self.longest(next, steps);
+            self.path.pop();
+            self.free[next] = true;
+        }
+    }
+
+    /// The most cells a walk from `pos` could still visit: no more than
+    /// can be reached, and since each step changes colour, no more than
+    /// one past twice the rarer colour.
+    fn reachable_bound(&self, pos: usize) -> usize {
+        let mut seen = vec![false; self.free.len()];
+        let mut to_visit = VecDeque::from([pos]);
+        let mut counts = [0, 0];
+        while let Some(cell) = to_visit.pop_front() {
+            for &(next, _) in &self.neighbours[cell] {
+                if self.free[next] && !seen[next] {
+                    seen[next] = true;
+                    counts[self.colour(next)] += 1;
+                    to_visit.push_back(next);
+                }
+            }
+        }
+        let other = counts[1 - self.colour(pos)];
+        let same = counts[self.colour(pos)];
+        other.min(same) * 2 + usize::from(other > same)
+    }
 
+    fn colour(&self, cell: usize) -> usize {
+        (cell / self.cols + cell % self.cols) % 2
+    }
+
+    fn free_degree(&self, cell: usize) -> usize {
+        self.neighbours[cell]
+            .iter()
+            .filter(|(next, _)| self.free[*next])
+            .count()
+    }
+
+    /// Cheap checks that the free cells can still be walked in one go from
+    /// `pos`: every free cell must be reachable, the cells must balance out
+    /// between the two colours of a chessboard, and free cells with only
+    /// one free neighbour can only be where the walk goes next or where it
+    /// ends, so there can be at most one of those that isn't next to `pos`.
+    fn can_still_fill(&self, pos: usize, remaining: usize) -> bool {
+        let mut dead_ends = 0;
+        let mut far_dead_ends = 0;
+        let mut same_colour = 1;
+        for cell in (0..self.free.len()).filter(|&cell| self.free[cell]) {
+            if self.colour(cell) == self.colour(pos) {
+                same_colour += 1;
+            }
+            let degree = self.free_degree(cell);
+            let next_to_pos = self.neighbours[cell].iter().any(|(n, _)| *n == pos);
+            if degree == 0 && !(next_to_pos && remaining == 1) {
+                return false;
+            }
+            if degree <= 1 {
+                dead_ends += 1;
+                if !next_to_pos {
+                    far_dead_ends += 1;
+                }
+            }
+        }
+        if dead_ends > 2 || far_dead_ends > 1 {
+            return false;
+        }
+        // Like a chessboard, every step changes colour, so the walk from
+        // `pos` has either as many cells of its colour as of the other, or
+        // one more.
+        let other_colour = remaining + 1 - same_colour;
+        if same_colour != other_colour && same_colour != other_colour + 1 {
+            return false;
+        }
+
+        let mut seen = vec![false; self.free.len()];
+        let mut to_visit = VecDeque::from([pos]);
+        let mut reached = 0;
+        while let Some(cell) = to_visit.pop_front() {
+            for &(next, _) in &self.neighbours[cell] {
+                if self.free[next] && !seen[next] {
+                    seen[next] = true;
+                    reached += 1;
+                    to_visit.push_back(next);
+                }
+            }
+        }
+        reached == remaining
+    }
 }
 
-pub fn solve_part_two(mut data: ProcessedInput) -> Result<()> {
+pub fn solve_part_two(_data: ProcessedInput) -> Result<()> {
     bail!("Unimplemented");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_completely_filled(m: &Map<SCell>) -> bool {
+        for (_, cell) in m {
+            let valid = matches!(cell, SCell::Empty(true) | SCell::Player | SCell::Wall);
+            if !valid {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn fill(input: &str) -> (Walk, Map<SCell>) {
+        let map = process_input(input.to_string()).unwrap();
+        let walk = Walk::find(&map).unwrap().expect("Map can be filled");
+        let filled = walk.fill(&map);
+        (walk, filled)
+    }
+
+    #[test]
+    fn fills_a_snake() {
+        let (walk, filled) = fill("X...\n###.\n....\n");
+        assert_eq!(walk.directions.len(), 8);
+        assert!(is_completely_filled(&filled));
+        assert_eq!(filled.to_string(), "OOOO\n###O\nXOOO");
+    }
+
+    #[test]
+    fn fills_an_open_room() {
+        let (walk, filled) = fill(".....\n.....\n..X..\n.....\n.....\n");
+        assert_eq!(walk.directions.len(), 24);
+        assert!(is_completely_filled(&filled));
+    }
+
+    #[test]
+    fn longest_walk_when_unfillable() {
+        // Both arms of the T can't be walked without doubling back.
+        let map = process_input("..X..\n##.##\n".to_string()).unwrap();
+        assert!(Walk::find(&map).unwrap().is_none());
+        assert_eq!(Walk::count_fills(&map, 5).unwrap(), 0);
+        assert!(solve_part_one(map.clone()).is_err());
+
+        let Longest::Proven(walk) = Walk::longest(&map).unwrap() else {
+            panic!("A map this small should be searched in full");
+        };
+        assert_eq!(walk.directions.len(), 2);
+        assert!(!is_completely_filled(&walk.fill(&map)));
+    }
+
+    #[test]
+    fn rules_out_large_open_maps_quickly() {
+        // An open 7x7 map has one more cell of the colour the player isn't
+        // standing on, so no walk can fill it and the longest must miss one.
+        let mut input = ".X.....\n".to_string();
+        input.push_str(&".......\n".repeat(6));
+        let map = process_input(input).unwrap();
+        assert!(solve_part_one(map.clone()).is_err());
+        let Longest::Proven(walk) = Walk::longest(&map).unwrap() else {
+            panic!("The colour bound should finish the search");
+        };
+        assert_eq!(walk.directions.len(), 47);
+    }
+}